@@ -15,13 +15,18 @@ criterion_group!(
     game::board_rotate_piece,
     game::board_move_piece,
     game::board_get_board,
-    game::board_get_highscore,
+    game::board_add_garbage,
+    game::board_snapshot,
+    game::board_restore,
+    game::highscore_parse,
     game::board_update_shadow,
     game::board_get_speed,
     game::board_update,
+    game::board_lock_piece,
     game::board_set_piece,
     game::board_update_rows,
     game::board_update_progress,
+    game::board_get_spin,
     game::board_next_piece,
     game::board_check_collision,
     
@@ -94,7 +94,7 @@ pub fn board_rotate_piece(c: &mut Criterion) {
     c.bench_function("game::Board::rotate_piece", move |b| {
         b.iter_batched(
             || board.clone(),
-            |mut board| assert!(board.rotate_piece()),
+            |mut board| assert!(board.rotate_piece(Rotate::Clockwise)),
             BatchSize::SmallInput
         )
     });
@@ -147,14 +147,44 @@ pub fn board_get_board(c: &mut Criterion) {
 
 
 
+pub fn board_add_garbage(c: &mut Criterion) {
+    let board = Board::new_board().unwrap();
+    c.bench_function("game::Board::add_garbage", move |b| {
+        b.iter_batched(
+            || board.clone(),
+            |mut board| assert!(board.add_garbage(4, GarbageHoles::Cheese)),
+            BatchSize::SmallInput
+        )
+    });
+}
+
+pub fn board_snapshot(c: &mut Criterion) {
+    let board = Board::new_board().unwrap();
+    c.bench_function("game::Board::snapshot", |b| b.iter(||
+        board.snapshot()
+    ));
+}
+
+pub fn board_restore(c: &mut Criterion) {
+    let mut board = Board::new_board().unwrap();
+    let snapshot = board.snapshot();
+    c.bench_function("game::Board::restore", move |b| b.iter(||
+        board.restore(&snapshot).unwrap()
+    ));
+}
+
 //ALL PRIVATE FUNCTIONS
 
-/// [74.382 us 75.214 us 76.237 us]
-/// [72.556 us 73.141 us 73.845 us]     AFTER MOVEMENT REWORK
-/// [76.028 us 84.301 us 102.26 us]
-pub fn board_get_highscore(c: &mut Criterion) {
-    c.bench_function("game::Board::get_highscore", |b| b.iter(||
-        assert_ne!(tests::get_highscore().unwrap(), 0)
+///parses a full leaderboard from memory so benching doesnt touch the real scores file
+pub fn highscore_parse(c: &mut Criterion) {
+    let mut contents = tetris::highscore::Leaderboard::default().to_string();
+    for mode in Mode::ALL.iter() {
+        for i in 0..tetris::highscore::LEADERBOARD_SIZE {
+            contents.push_str(&format!("{}|{}|{}|{}|{}|1591000000|PLAYER {}\n", mode, 100000-i, 10, 100, 3600+i, i));
+        }
+    }
+    c.bench_function("highscore::Leaderboard::from_str", move |b| b.iter(||
+        contents.parse::<tetris::highscore::Leaderboard>().unwrap()
     ));
 }
 
@@ -180,7 +210,7 @@ pub fn board_update_shadow(c: &mut Criterion) {
 pub fn board_get_speed(c: &mut Criterion) {
     let board = Board::new_board().unwrap();
     c.bench_function("game::Board::get_speed", |b| b.iter(||
-        assert_eq!(tests::get_speed(&board), 48)
+        assert_eq!(tests::get_speed(&board).frames, 48)
     ));
 }
 
@@ -200,6 +230,17 @@ pub fn board_update(c: &mut Criterion) {
     });
 }
 
+pub fn board_lock_piece(c: &mut Criterion) {
+    let board = Board::new_board().unwrap();
+    c.bench_function("game::Board::lock_piece", move |b| {
+        b.iter_batched(
+            || board.clone(),
+            |mut board| tests::lock_piece(&mut board),
+            BatchSize::SmallInput
+        )
+    });
+}
+
 /// [91.319 us 99.712 us 115.03 us]
 /// [88.113 us 91.728 us 96.726 us] AFTER PIECE INDEX
 /// [88.423 us 93.115 us 101.65 us] AFTER MOVEMENT REWORK
@@ -235,12 +276,19 @@ pub fn board_update_progress(c: &mut Criterion) {
     c.bench_function("game::Board::update_progress", move |b| {
         b.iter_batched(
             || board.clone(),
-            |mut board| tests::update_progress(&mut board, vec!(1, 2, 3)),
+            |mut board| tests::update_progress(&mut board, vec!(1, 2, 3), Spin::Full),
             BatchSize::SmallInput
         )
     });
 }
 
+pub fn board_get_spin(c: &mut Criterion) {
+    let board = Board::new_board().unwrap();
+    c.bench_function("game::Board::get_spin", |b| b.iter(||
+        assert_eq!(tests::get_spin(&board), Spin::None)
+    ));
+}
+
 /// [166.42 us 170.07 us 174.58 us]
 /// [4.5054 us 4.5787 us 4.6587 us] AFTER PIECE INDEX
 /// [448.44 ns 459.70 ns 471.48 ns] AFTER MOVEMENT REWORK
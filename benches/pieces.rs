@@ -8,7 +8,7 @@ use tetris::game::pieces::*;
 /// [55.494 ns 56.713 ns 58.157 ns] FLAT BOARD
 pub fn piece_gen_piece(c: &mut Criterion) {
     let index = PieceType::gen_piece_index();
-    let type_ = PieceType::pick_random();
+    let type_ = PieceType::pick_random(&mut rand::thread_rng());
     c.bench_function("pieces::Piece::gen_piece", |b| b.iter(||
         assert!(Piece::gen_piece(type_, (0, 0), &index).can_hold)
     ));
@@ -21,7 +21,7 @@ pub fn piece_gen_piece(c: &mut Criterion) {
 /// [110.91 ns 113.43 ns 116.20 ns] FLAT BOARD
 pub fn piece_get_rotated(c: &mut Criterion) {
     let index = PieceType::gen_piece_index();
-    let type_ = PieceType::pick_random();
+    let type_ = PieceType::pick_random(&mut rand::thread_rng());
     let piece = Piece::gen_piece(type_, (0, 0), &index);
     c.bench_function("pieces::Piece::get_rotated", move |b| b.iter(||
         assert!(piece.get_rotated().can_hold)
@@ -35,7 +35,7 @@ pub fn piece_get_rotated(c: &mut Criterion) {
 /// [80.041 ns 80.963 ns 81.936 ns] FLAT BOARD
 pub fn piece_reset_rotation(c: &mut Criterion) {
     let index = PieceType::gen_piece_index();
-    let type_ = PieceType::pick_random();
+    let type_ = PieceType::pick_random(&mut rand::thread_rng());
     let piece = Piece::gen_piece(type_, (0, 0), &index);
     c.bench_function("game::Board::reset_rotation", move |b| {
         b.iter_batched(
@@ -53,7 +53,7 @@ pub fn piece_reset_rotation(c: &mut Criterion) {
 /// [470.26 ps 475.92 ps 481.92 ps] 4% SLOWER
 pub fn piece_get_down(c: &mut Criterion) {
     let index = PieceType::gen_piece_index();
-    let type_ = PieceType::pick_random();
+    let type_ = PieceType::pick_random(&mut rand::thread_rng());
     let piece = Piece::gen_piece(type_, (0, 0), &index);
     c.bench_function("pieces::Piece::get_down", move |b| b.iter(||
         piece.get_down()
@@ -65,7 +65,7 @@ pub fn piece_get_down(c: &mut Criterion) {
 /// [448.07 ps 449.57 ps 451.35 ps] AFTER MOVEMENT REWORK
 pub fn piece_get_left(c: &mut Criterion) {
     let index = PieceType::gen_piece_index();
-    let type_ = PieceType::pick_random();
+    let type_ = PieceType::pick_random(&mut rand::thread_rng());
     let piece = Piece::gen_piece(type_, (0, 0), &index);
     c.bench_function("pieces::Piece::get_left", move |b| b.iter(||
         piece.get_left()
@@ -78,7 +78,7 @@ pub fn piece_get_left(c: &mut Criterion) {
 /// [456.60 ps 461.10 ps 466.07 ps] FLAT BOARD
 pub fn piece_get_right(c: &mut Criterion) {
     let index = PieceType::gen_piece_index();
-    let type_ = PieceType::pick_random();
+    let type_ = PieceType::pick_random(&mut rand::thread_rng());
     let piece = Piece::gen_piece(type_, (0, 0), &index);
     c.bench_function("pieces::Piece::get_right", move |b| b.iter(||
         piece.get_right()
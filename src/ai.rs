@@ -3,9 +3,12 @@ use crate::game::*;
 use dynerr::*;
 
 use std::{thread, fmt};
+use std::collections::{HashMap, VecDeque};
 use std::cmp::Ordering;
 use std::sync::{Arc, Mutex, mpsc, PoisonError, MutexGuard};
 
+///how many values are in AiParameters
+pub const PARAMETER_COUNT: usize = 17;
 
 #[derive(Clone, Copy, PartialEq, PartialOrd)]
 pub struct AiParameters {
@@ -21,12 +24,21 @@ pub struct AiParameters {
     pub current_holes_importance: f32,
     pub max_pillar_height: f32,
     pub current_pillars_importance: f32,
+    pub row_transitions_importance: f32,
+    pub column_transitions_importance: f32,
+    pub well_depth_importance: f32,
+    pub hole_depth_importance: f32,
+    pub rows_with_holes_importance: f32,
+    //positive
+    pub eroded_cells_importance: f32,
+    //negative
+    pub landing_height_importance: f32,
 }
 
 impl fmt::Display for AiParameters {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f,
-            "{:.01} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.01} : {:.05}",
+            "{:.01} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.01} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05} : {:.05}",
             self.min_lines_to_clear,
             self.lines_cleared_importance,
             self.points_scored_importance,
@@ -36,13 +48,56 @@ impl fmt::Display for AiParameters {
             self.height_variation_importance,
             self.current_holes_importance,
             self.max_pillar_height,
-            self.current_pillars_importance
+            self.current_pillars_importance,
+            self.row_transitions_importance,
+            self.column_transitions_importance,
+            self.well_depth_importance,
+            self.hole_depth_importance,
+            self.rows_with_holes_importance,
+            self.eroded_cells_importance,
+            self.landing_height_importance,
         )
     }
 }
 
+///the best parameters found by training so far
+impl Default for AiParameters {
+    fn default() -> Self {
+        //         ???  |    ?? |    ??? | 2   : 0.919   : 0.006   : 0.572   : 0.049   : 0.143   : 0.012   : 0.995   : 0   : 0.392
+        //      392860  |    8  |    227 | 2   : 0.939   : 0.004   : 0.240   : 0.002   : 0.102   : 0.024   : 0.920   : 0   : 0.200
+        //      693944  |    6  |    196 | 4   : 0.79000 : 0.00100 : 0.14900 : 0.05000 : 0.09200 : 0.01900 : 0.63200 : 1   : 0.06600
+        //      1189945 |   18  |    485 | 4   : 0.79000 : 0.00100 : 0.33926 : 0.05000 : 0.09200 : 0.01900 : 0.42276 : 0   : 0.06300
+        //      1145311 |   21  |    556 | 4.0 : 0.91910 : 0.00000 : 0.56862 : 0.09960 : 0.32070 : 0.06357 : 1.00564 : 1.0 : 0.38466
+        //208 | 1170614 |   16  |    443 | 0.0 : -0.61509 : 0.00227 : 1.49888 : 0.89981 : -1.63555 : -0.83318 : 1.94491 : 0.0 : 0.90794
+        // 75 | 1158106 |   17  |    475 | 4.0 : 2.81089 : 0.00000 : 1.44516 : 0.00000 : 1.38000 : 0.12947 : 2.62338 : 0.0 : 0.97880
+        //202 | 1037750 |    8  |    252 | 4.0 : 0.78193 : 0.00000 : 0.51338 : 0.09517 : 0.00000 : 0.04101 : 1.00000 : 0.0 : 0.22724
+        //251 | 2393169 |   37  |    958 | 4.0 : 0.91413 : 0.00000 : 0.66610 : 0.01078 : 0.22913 : 0.05655 : 0.78533 : 0.0 : 0.27396        9.36M lvl 157 i think thats good enough tbh. it got 60k per level...my record is like 32k/lvl
+        // 38 | 2030640 |   40  |   1051 | 4.0 : 0.83434 : 0.00000 : 0.98846 : 0.04482 : 0.09175 : 0.00000 : 0.89960 : 0.0 : 0.34672        7.71M lvl 166
+        Self {
+            min_lines_to_clear:             4.0,
+            lines_cleared_importance:       0.83434,
+            points_scored_importance:       0.00000,
+            piece_depth_importance:         0.98846,
+            max_height_importance:          0.04482,
+            avg_height_importance:          0.09175,
+            height_variation_importance:    0.00000,
+            current_holes_importance:       0.89960,
+            max_pillar_height:              0.0,
+            current_pillars_importance:     0.34672,
+            //not trained yet so they dont change how the AI plays
+            row_transitions_importance:     0.0,
+            column_transitions_importance:  0.0,
+            well_depth_importance:          0.0,
+            hole_depth_importance:          0.0,
+            rows_with_holes_importance:     0.0,
+            eroded_cells_importance:        0.0,
+            landing_height_importance:      0.0,
+        }
+    }
+}
+
 impl AiParameters {
-    pub fn construct(params: [f32;10]) -> Self {
+    pub fn construct(params: [f32; PARAMETER_COUNT]) -> Self {
         Self {
             min_lines_to_clear:             params[0],
             lines_cleared_importance:       params[1],
@@ -54,10 +109,17 @@ impl AiParameters {
             current_holes_importance:       params[7],
             max_pillar_height:              params[8],
             current_pillars_importance:     params[9],
+            row_transitions_importance:     params[10],
+            column_transitions_importance:  params[11],
+            well_depth_importance:          params[12],
+            hole_depth_importance:          params[13],
+            rows_with_holes_importance:     params[14],
+            eroded_cells_importance:        params[15],
+            landing_height_importance:      params[16],
         }
     }
 
-    pub fn deconstruct(self) -> [f32;10] {
+    pub fn deconstruct(self) -> [f32; PARAMETER_COUNT] {
         [
             self.min_lines_to_clear,
             self.lines_cleared_importance,
@@ -69,49 +131,272 @@ impl AiParameters {
             self.current_holes_importance,
             self.max_pillar_height,
             self.current_pillars_importance,
+            self.row_transitions_importance,
+            self.column_transitions_importance,
+            self.well_depth_importance,
+            self.hole_depth_importance,
+            self.rows_with_holes_importance,
+            self.eroded_cells_importance,
+            self.landing_height_importance,
         ]
     }
 }
 
 
-///possible piece movements
-#[derive(Debug, Clone)]
+///possible piece movements. each one is a single key press
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Move {
     Left,
     Right,
-    Rotate,
+    ///holds left until the piece hits something
+    DasLeft,
+    ///holds right until the piece hits something
+    DasRight,
+    ///soft drops this many cells without locking
+    SoftDrop(usize),
+    Rotate(Rotate),
     Drop,
     Hold,
     Restart,
     None,
 }
 
-///current piece rotation relative to its start
-#[derive(Clone, Copy, Debug)]
-enum Rotation {
-    North,
-    South,
-    East,
-    West,
+impl Move {
+    ///true for the presses finesse counts. the ones that move or turn the piece
+    pub fn is_key_press(&self) -> bool {
+        match self {
+            Self::Left | Self::Right | Self::DasLeft | Self::DasRight | Self::SoftDrop(_) | Self::Rotate(_) => true,
+            Self::Drop | Self::Hold | Self::Restart | Self::None => false,
+        }
+    }
+}
+
+///a board after a piece has been placed and its lines cleared. what evaluators score
+pub struct Placement<'a> {
+    ///the board after the clear. width*height cells, row major from the top
+    pub board: &'a [bool],
+    pub width: usize,
+    pub height: usize,
+    ///where the piece locked
+    pub location: (isize, isize),
+    ///true if the piece was swapped out of hold first
+    pub is_held: bool,
+    pub lines_cleared: usize,
+    ///points the clear scored including combo and back to back bonuses
+    pub points_scored: usize,
+    ///how many of the pieces own cells were cleared times the lines cleared
+    pub eroded_cells: usize,
+    ///the average height of the pieces cells from the floor before the clear
+    pub landing_height: f32,
+    ///the board before the piece was placed
+    pub state: &'a StrippedBoard,
+}
+
+impl<'a> Placement<'a> {
+    ///the height of every column from left to right. 0 is an empty column
+    pub fn column_heights(&self) -> Vec<usize> {
+        (0..self.width).map(|x| {
+            (0..self.height)
+                .find(|y| self.board[y*self.width+x])
+                .map_or(0, |y| self.height-y)
+        }).collect()
+    }
+
+    ///how many empty spaces have blocks over them
+    pub fn holes(&self) -> usize {
+        let mut holes = 0;
+        for x in 0..self.width {
+            let mut idx = x;
+            let mut under = false;
+            for _ in 0..self.height {
+                if self.board[idx] {under = true}
+                else if under {holes+=1}
+                idx += self.width;
+            }
+        }
+        holes
+    }
+
+    ///each additional block for pillars over max_pillar_height blocks
+    pub fn pillars(&self, max_pillar_height: f32) -> f32 {
+        let mut pillars = 0.0;
+        for x in 0..self.width {
+            let mut idx = x;
+            let mut pillar_height = 0;
+            for _ in 0..self.height {
+                if !self.board[idx]
+                && (
+                    *self.board.get((idx).checked_sub(1).unwrap_or(9999)).unwrap_or(&true)                         //SLOPPY SOLUTION
+                    || x == 0                                                                                           //CHECK IF EDGE OF SCREEN
+                ) && (
+                    *self.board.get(idx).unwrap_or(&true)
+                    || x == self.width-1                                                                          //CHECK IF EDGE OF SCREEN
+                ) {
+                    pillar_height+=1
+                }
+                idx += self.width;
+            }
+            if pillar_height as f32 > max_pillar_height {pillars+=pillar_height as f32-max_pillar_height}
+        }
+        pillars
+    }
+
+    ///true if the cell is filled. walls and the floor count as filled
+    fn filled(&self, x: isize, y: isize) -> bool {
+        x < 0 || x >= self.width as isize || y >= self.height as isize || self.board[y as usize*self.width+x as usize]
+    }
+
+    ///how many times a cell and the one next to it differ along each row. the walls count as filled
+    pub fn row_transitions(&self) -> usize {
+        (0..self.height as isize).map(|y| {
+            (0..=self.width as isize).filter(|x| self.filled(x-1, y) != self.filled(*x, y)).count()
+        }).sum()
+    }
+
+    ///how many times a cell and the one under it differ down each column. the floor counts as filled
+    pub fn column_transitions(&self) -> usize {
+        (0..self.width as isize).map(|x| {
+            (0..self.height as isize).filter(|y| self.filled(x, *y) != self.filled(x, y+1)).count()
+        }).sum()
+    }
+
+    ///every well summed as 1+2+..+depth. a well cell is empty with something on both sides of it
+    pub fn well_depth(&self) -> usize {
+        let mut wells = 0;
+        for x in 0..self.width as isize {
+            let mut depth = 0;
+            for y in 0..self.height as isize {
+                if !self.filled(x, y) && self.filled(x-1, y) && self.filled(x+1, y) {
+                    depth += 1;
+                    wells += depth;
+                } else {depth = 0}
+            }
+        }
+        wells
+    }
+
+    ///how many filled cells are stacked over each hole, summed
+    pub fn hole_depth(&self) -> usize {
+        let mut depth = 0;
+        for x in 0..self.width as isize {
+            let mut above = 0;
+            for y in 0..self.height as isize {
+                if self.filled(x, y) {above += 1}
+                else {depth += above}
+            }
+        }
+        depth
+    }
+
+    ///how many rows have at least one hole in them
+    pub fn rows_with_holes(&self) -> usize {
+        let mut covered = vec!(false; self.width);
+        let mut rows = 0;
+        for row in self.board.chunks(self.width) {
+            if row.iter().zip(covered.iter()).any(|(cell, covered)| !cell && *covered) {rows += 1}
+            for (cell, covered) in row.iter().zip(covered.iter_mut()) {*covered |= *cell}
+        }
+        rows
+    }
+}
+
+///scores placements so the AI can pick the best one. the higher the score the better.
+///implement this to give the AI a different heuristic, then pass it to start()
+pub trait Evaluator {
+    fn evaluate(&self, placement: &Placement) -> f32;
+
+    ///the parts that made up a score. only used for logging to ai.log
+    fn debug_scores(&self, _placement: &Placement) -> Vec<f32> {
+        Vec::new()
+    }
+}
+
+///the default heuristic. a weighted sum of board features
+impl Evaluator for AiParameters {
+    fn evaluate(&self, placement: &Placement) -> f32 {
+        let [lines_cleared, points_scored, piece_depth, max_height, avg_height, height_variation, current_holes, current_pillars,
+            row_transitions, column_transitions, well_depth, hole_depth, rows_with_holes, eroded_cells, landing_height] = self.weighted_features(placement);
+        lines_cleared+points_scored+piece_depth+max_height+avg_height+height_variation+current_holes+current_pillars
+            +row_transitions+column_transitions+well_depth+hole_depth+rows_with_holes+eroded_cells+landing_height
+    }
+
+    fn debug_scores(&self, placement: &Placement) -> Vec<f32> {
+        self.weighted_features(placement).to_vec()
+    }
+}
+
+impl AiParameters {
+    ///every weighted feature. the ones the AI avoids are negative
+    fn weighted_features(&self, placement: &Placement) -> [f32; 15] {
+        let cleared = placement.lines_cleared as f32;
+        //gets how many lines cleared adjusted for min_lines_to_clear importance
+        let lines_cleared     = (cleared*self.lines_cleared_importance)*{if cleared >= self.min_lines_to_clear {1.0} else {-1.0}};
+        //points scored by the clear
+        let points_scored     = placement.points_scored as f32*self.points_scored_importance;
+        //gets how far down the piece was placed
+        let piece_depth       = placement.location.1 as f32*self.piece_depth_importance;                                           //y location should always be positive
+        //gets heights of every column
+        let column_heights    = placement.column_heights();
+        let tallest           = column_heights.iter().max().copied().unwrap_or(placement.height);
+        let shortest          = column_heights.iter().min().copied().unwrap_or(0);
+        //tallest column
+        let max_height        = tallest as f32*self.max_height_importance;
+        //average column height
+        let avg_height        = (column_heights.iter().sum::<usize>() as f32/column_heights.len() as f32)*self.avg_height_importance;
+        //tallest column - smallest column
+        let height_variation  = ((tallest-shortest) as f32)*self.height_variation_importance;
+        //how many gaps exist in columns
+        let current_holes     = placement.holes() as f32*self.current_holes_importance;
+        //how many spots where empty spaces surrounded by filled spaces on either side exist (over the set max allowed pillar height)
+        let current_pillars   = placement.pillars(self.max_pillar_height)*self.current_pillars_importance;
+        //how jagged the rows are
+        let row_transitions   = placement.row_transitions() as f32*self.row_transitions_importance;
+        //how jagged the columns are. mostly from holes and overhangs
+        let column_transitions = placement.column_transitions() as f32*self.column_transitions_importance;
+        //how deep the wells are. deeper ones count for more
+        let well_depth        = placement.well_depth() as f32*self.well_depth_importance;
+        //how buried the holes are
+        let hole_depth        = placement.hole_depth() as f32*self.hole_depth_importance;
+        //how many rows cant be cleared without digging
+        let rows_with_holes   = placement.rows_with_holes() as f32*self.rows_with_holes_importance;
+        //how much of the piece went away with the clear
+        let eroded_cells      = placement.eroded_cells as f32*self.eroded_cells_importance;
+        //how high up the piece landed
+        let landing_height    = placement.landing_height*self.landing_height_importance;
+
+        [
+            lines_cleared, points_scored, piece_depth, -max_height, -avg_height, -height_variation, -current_holes, -current_pillars,
+            -row_transitions, -column_transitions, -well_depth, -hole_depth, -rows_with_holes, eroded_cells, -landing_height,
+        ]
+    }
 }
 
 struct MoveData {
     location: (isize, isize),
     is_held: bool,
-    rotation: Rotation,
+    rotation: pieces::Rotation,
+    ///the inputs that get the piece from spawn to where it locks, not counting hold or the hard drop
+    path: Vec<Move>,
+    ///the spin the path locks the piece with
+    spin: Spin,
     board: Vec<bool>,
+    width: usize,
+    height: usize,
+    lines_cleared: usize,
+    points_scored: usize,
+    eroded_cells: usize,
+    landing_height: f32,
     value: f32,
-    debug_scores: Vec<f32>,
 }
 
 impl MoveData {
 
-    fn generate_data(mut board: Vec<bool>, piece: pieces::Piece, is_held: bool, rotation: Rotation, parameters: &AiParameters) -> Self {
+    fn generate_data(mut board: Vec<bool>, piece: pieces::Piece, is_held: bool, path: Vec<Move>, spin: Spin, evaluator: &dyn Evaluator, state: &StrippedBoard) -> Self {
         for (i, block) in piece.data.iter().enumerate() {
             if *block {
                 let row = i/piece.dim;
                 let column = i%piece.dim;
-                let board_index = (((piece.location.1+row as isize)*BOARD_WIDTH as isize) + (piece.location.0+column as isize)) as usize;           //USIZE WRAPPING
+                let board_index = (((piece.location.1+row as isize)*state.width as isize) + (piece.location.0+column as isize)) as usize;           //USIZE WRAPPING
                 if let Some(cell) = board.get_mut(board_index) {*cell = true}
             }
         }
@@ -120,168 +405,136 @@ impl MoveData {
             Self {
                 location: piece.location,
                 is_held,
-                rotation,
+                rotation: piece.rotation,
+                path,
+                spin,
                 board,
+                width: state.width,
+                height: state.height,
+                lines_cleared: 0,
+                points_scored: 0,
+                eroded_cells: 0,
+                landing_height: 0.0,
                 value: 0.0,
-                debug_scores: vec!(),
             }
         };
 
-        move_data.calc_board(parameters);
+        let piece_cells = cells(&piece);
+        move_data.landing_height = piece_cells.iter().map(|(_, y)| (state.height as isize-y) as f32).sum::<f32>()/piece_cells.len() as f32;
+        move_data.calc_board(&piece_cells, evaluator, state);
         move_data
     }
 
-    /// calculates the move score. the higher the score the better
-    /// also calcs the next board
-    fn calc_board(&mut self, parameters: &AiParameters) {
-        let (scored, cleared) = self.do_clear();
-        //gets how many lines cleared adjusted for min_lines_to_clear importance
-        let lines_cleared     = (cleared*parameters.lines_cleared_importance)*{if cleared >= parameters.min_lines_to_clear {1.0} else {-1.0}};
-        //updates board and gets points scored
-        let points_scored     = scored*parameters.points_scored_importance;
-        //gets how far down the piece was placed
-        let piece_depth       = self.location.1 as f32*parameters.piece_depth_importance;                                           //y location should always be positive
-        //gets heights of every column
-        let column_heights    = self.get_heights();
-        //tallest column
-        let max_height        = *column_heights.last().unwrap() as f32*parameters.max_height_importance;                                        //DIRECT UNWRAP
-        //average column height
-        let avg_height        = (column_heights.iter().sum::<usize>() as f32/column_heights.len() as f32)*parameters.avg_height_importance;
-        //tallest column - smallest column
-        let height_variation  = ((column_heights.last().unwrap_or(&BOARD_HEIGHT)-column_heights.first().unwrap_or(&0)) as f32)*parameters.height_variation_importance;
-        //how many gaps exist in columns
-        let current_holes     = self.calc_holes()*parameters.current_holes_importance;
-        //how many spots where empty spaces surrounded by filled spaces on either side exist (over the set max allowed pillar height)
-        let current_pillars   = self.calc_pillars(parameters.max_pillar_height)*parameters.current_pillars_importance;
-
-        self.debug_scores = vec!(lines_cleared, points_scored, piece_depth, max_height, avg_height, height_variation, current_holes, current_pillars);
-        self.value = lines_cleared+points_scored+piece_depth-max_height-avg_height-height_variation-current_holes-current_pillars;
-    }
-
-    /// returns a list of all column heights.
-    fn get_heights(&self) -> Vec<usize> {
-        let mut heights = Vec::new();
-        for x in 0..BOARD_WIDTH {
-            let mut idx = x;
-            for y in 0..BOARD_HEIGHT {
-                if self.board[idx] {
-                    heights.push((BOARD_HEIGHT-y) as usize);
-                    break
-                } else if y+1 == BOARD_HEIGHT {
-                    heights.push(0);
-                    break
-                }
-                idx += BOARD_WIDTH;
-            }
-        }
-        heights.sort();
-        heights
+    /// clears the board then scores it with the evaluator. the higher the score the better
+    fn calc_board(&mut self, piece_cells: &[(isize, isize)], evaluator: &dyn Evaluator, state: &StrippedBoard) {
+        let (points_scored, cleared) = self.do_clear(state);
+        let lines_cleared = cleared.len();
+        let piece_cleared = piece_cells.iter().filter(|(_, y)| cleared.contains(&((self.height as isize-y) as usize))).count();
+        self.eroded_cells = piece_cleared*lines_cleared;
+        self.points_scored = points_scored;
+        self.lines_cleared = lines_cleared;
+        self.value = evaluator.evaluate(&self.placement(state));
     }
 
-    ///how many empty spaces have blocks over them
-    fn calc_holes(&self) -> f32 {
-        let mut holes = 0;
-        for x in 0..BOARD_WIDTH {
-            let mut idx = x;
-            let mut under = false;
-            for _ in 0..BOARD_HEIGHT {
-                if self.board[idx] {under = true}
-                else if !self.board[idx]
-                && under {holes+=1}
-                idx += BOARD_WIDTH;
-            }
+    ///what the evaluator sees of this move
+    fn placement<'a>(&'a self, state: &'a StrippedBoard) -> Placement<'a> {
+        Placement {
+            board: &self.board,
+            width: self.width,
+            height: self.height,
+            location: self.location,
+            is_held: self.is_held,
+            lines_cleared: self.lines_cleared,
+            points_scored: self.points_scored,
+            eroded_cells: self.eroded_cells,
+            landing_height: self.landing_height,
+            state,
         }
-        holes as f32
     }
 
-    ///each additional block for pillars over 2 blocks
-    fn calc_pillars(&self, max_pillar_height: f32) -> f32 {
-        let mut pillars = 0.0;
-        for x in 0..BOARD_WIDTH {
-            let mut idx = x;
-            let mut pillar_height = 0;
-            for _ in 0..BOARD_HEIGHT {
-                if !self.board[idx]
-                && (
-                    *self.board.get((idx).checked_sub(1).unwrap_or(9999)).unwrap_or(&true)                         //SLOPPY SOLUTION
-                    || x == 0                                                                                           //CHECK IF EDGE OF SCREEN
-                ) && (
-                    *self.board.get(idx).unwrap_or(&true)
-                    || x == BOARD_WIDTH-1                                                                          //CHECK IF EDGE OF SCREEN
-                ) {
-                    pillar_height+=1
-                }
-                idx += BOARD_WIDTH;
-            }
-            if pillar_height as f32 > max_pillar_height {pillars+=pillar_height as f32-max_pillar_height}
+    ///the board after this move once the next piece spawns. None if the preview queue doesnt go far enough to know the next piece
+    fn next_board(&self, state: &StrippedBoard) -> Option<StrippedBoard> {
+        let mut next_pieces = state.next_pieces.clone();
+        let mut held_piece = state.held_piece.clone();
+        if self.is_held {
+            //the current piece goes back to spawn facing north. if nothing was held the piece placed came off the queue
+            let mut current = state.piece.get_turned((4-state.piece.rotation.index())%4);
+            current.location = next_pieces.first().map_or(current.location, |p| p.location);
+            if held_piece.is_none() && !next_pieces.is_empty() {next_pieces.remove(0);}
+            held_piece = Some(current);
         }
-        pillars
+        if next_pieces.is_empty() {return None}
+        let piece = next_pieces.remove(0);
+        let lines = state.lines+self.lines_cleared;
+        let mut next = StrippedBoard {
+            piece,
+            next_pieces,
+            held_piece,
+            data: self.board.clone(),
+            width: state.width,
+            height: state.height,
+            hidden: state.hidden,
+            score: state.score+self.points_scored,
+            level: lines/state.lines_per_level,
+            lines,
+            lines_per_level: state.lines_per_level,
+            combo: if self.lines_cleared == 0 {None} else {Some(state.combo.map_or(0, |c| c+1))},
+            back_to_back: match self.lines_cleared {
+                0 => state.back_to_back,
+                lines if scoring::is_difficult(lines, self.spin) => state.back_to_back+1,
+                _ => 0,
+            },
+            scoring: state.scoring,
+            gameover: false,
+        };
+        next.gameover = check_collision(&next, &next.piece, next.piece.location);
+        Some(next)
     }
 
-    //TODO if need be, i could make this return the exact rows cleared so AI could go after higher rows?
     //TODO update its benchmark so it actually clears rows while benching
-    ///clears rows, adds new empty rows, and returns points scored
-    fn do_clear(&mut self) -> (f32, f32) {
+    ///clears rows, adds new empty rows, and returns points scored including combo and back to back bonuses
+    ///and the heights of the cleared rows
+    fn do_clear(&mut self, state: &StrippedBoard) -> (usize, Vec<usize>) {
         let mut cleared = Vec::new();
-        for y in 0..BOARD_HEIGHT {
-            let start_range = y*BOARD_WIDTH;
-            let end_range = start_range+BOARD_WIDTH;
+        for y in 0..self.height {
+            let start_range = y*self.width;
+            let end_range = start_range+self.width;
             if self.board[start_range..end_range].iter().all(|b| *b) {
                 self.board.drain(start_range..end_range);
-                self.board.splice(0..0, vec!(false;BOARD_WIDTH));
-                cleared.push(BOARD_HEIGHT-y)
+                self.board.splice(0..0, vec!(false;self.width));
+                cleared.push(self.height-y)
             }
         }
-        let modifier = match cleared.len() {
-            1 => 40,
-            2 => 100,
-            3 => 300,
-            4 => 1200,
-            _ => 3600
-        };
-        (
-            cleared.iter().map(|y|modifier*(y+1)).sum::<usize>() as f32,
-            cleared.len() as f32
-        )
+        //the board levels up before scoring so the clear is scored on the level it leads to. spins score even without a clear
+        let level = (state.lines+cleared.len())/state.lines_per_level;
+        let combo = if cleared.is_empty() {0} else {state.combo.map_or(0, |c| c+1)};
+        let points = state.scoring.clear_points(&cleared, state.height-state.hidden, self.spin, level, combo, state.back_to_back);
+        (points, cleared)
     }
 
-    fn gen_input(&self, board: &StrippedBoard, log_flag: bool) -> Vec<Move>{
+    fn gen_input(&self, board: &StrippedBoard, evaluator: &dyn Evaluator, log_flag: bool) -> Vec<Move>{
         let mut moves = Vec::new();
         let piece = {
             if self.is_held {
                 moves.push(Move::Hold);
                 if let Some(held) = &board.held_piece {
                     held
-                } else {&board.next_piece}
+                } else {&board.next_pieces[0]}
             } else {&board.piece}
         };
-        let roto_times = {
-            match self.rotation {
-                Rotation::North => 0,
-                Rotation::West  => 1,
-                Rotation::South => 2,
-                Rotation::East  => 3,
-            }
-        };
-        for _ in 0..roto_times {moves.push(Move::Rotate)}
-        let distance = self.location.0 - piece.location.0;
-        if distance > 0 {
-            for _ in 0..distance {moves.push(Move::Right)}
-        } else if distance < 0 {
-            for _ in distance..0 {moves.push(Move::Left)}
-        }
+        moves.extend_from_slice(&self.path);
         moves.push(Move::Drop);
-        if log_flag {self.log(&piece, &moves)}
+        if log_flag {self.log(piece, &moves, &evaluator.debug_scores(&self.placement(board)))}
         moves
     }
 
     ///logs ai activity to ai.log
-    fn log(&self, piece: &pieces::Piece, moves: &Vec<Move>) {
+    fn log(&self, piece: &pieces::Piece, moves: &Vec<Move>, debug_scores: &[f32]) {
         log!(format!("target {:?}, {:?} got score: {}", self.location, self.rotation, self.value), "ai.log");   
         let mut scores = String::new();                                                                         
-        for score in &self.debug_scores {scores.push_str(&format!("{}, ", score))}                              
+        for score in debug_scores {scores.push_str(&format!("{}, ", score))}                              
         log!(scores, "ai.log");                                                                                 
-        for row in self.board.chunks(BOARD_WIDTH) {                                                             
+        for row in self.board.chunks(self.width) {                                                             
             let mut r = String::new();                                                                          
             for column in row {                                                                                 
                 if *column {                                                                                    
@@ -304,28 +557,18 @@ impl MoveData {
     }
 }
 
-///rotates piece data
-fn rotate_piece(piece: &mut pieces::Piece) {
-    let original = piece.data.clone();
-    for i in 0..piece.data.len() {
-        let column = i%piece.dim;
-        let row = i/piece.dim;
-        piece.data[(row*piece.dim)+column] = original[(column*piece.dim)+piece.dim-row-1];
-    }
-}
-
-///checks piece for collision on board
-fn check_collision(board: &Vec<bool>, piece: &pieces::Piece) -> bool {
+///checks piece for collision on board if it was at location
+fn check_collision(board: &StrippedBoard, piece: &pieces::Piece, location: (isize, isize)) -> bool {
     for i in 0..piece.data.len() {
         if piece.data[i] {
             let row = i/piece.dim;
             let column = i%piece.dim;
-            if (piece.location.0+column as isize) < 0
-            || (piece.location.0+column as isize) > BOARD_WIDTH as isize-1
-            || (piece.location.1+row as isize) < 0
-            || (piece.location.1+row as isize) > BOARD_HEIGHT as isize-1
+            if (location.0+column as isize) < 0
+            || (location.0+column as isize) > board.width as isize-1
+            || (location.1+row as isize) < 0
+            || (location.1+row as isize) > board.height as isize-1
                 {return true}
-            if let Some(cell) = board.get((((piece.location.1+row as isize)*BOARD_WIDTH as isize)+(piece.location.0+column as isize)) as usize) {                      //RELIES ON USIZE WRAPPING
+            if let Some(cell) = board.data.get((((location.1+row as isize)*board.width as isize)+(location.0+column as isize)) as usize) {                      //RELIES ON USIZE WRAPPING
                 if *cell {return true}
             } else {return true}
         }
@@ -333,72 +576,280 @@ fn check_collision(board: &Vec<bool>, piece: &pieces::Piece) -> bool {
     false
 }
 
-///get all possible moves for a piece
-fn get_moves_for_piece(board: &StrippedBoard, mut piece: pieces::Piece, is_held: bool, parameters: &AiParameters) -> Vec<MoveData> {
-    let mut possible_moves =  Vec::new();
-    let original_location = piece.location;
-    for rotation in [Rotation::North, Rotation::East, Rotation::South, Rotation::West].iter() {
-        //move to left edge
-        while !check_collision(&board.data, &piece) {
-            piece.location.0-=1;
+///a spot the piece can be in. the pieces location and rotation
+type Position = (isize, isize, pieces::Rotation);
+
+///finds every spot a piece can lock in from where it is using shifts, DAS, rotations and soft drops.
+///returns the piece placed over each set of cells with the fewest inputs that get it there, not counting the hard drop,
+///and the spin it locks with if those inputs end on a rotation
+fn find_placements(board: &StrippedBoard, piece: &pieces::Piece) -> Vec<(pieces::Piece, Vec<Move>, Spin)> {
+    //the piece in each rotation so the search doesnt have to keep turning it
+    let shapes = pieces::Rotation::ALL.iter()
+        .map(|r| piece.get_turned((4+r.index()-piece.rotation.index())%4))
+        .collect::<Vec<pieces::Piece>>();
+    let collides = |(x, y, rotation): Position| check_collision(board, &shapes[rotation.index()], (x, y));
+    //the first kick that fits is the one the board will use
+    let rotate = |(x, y, rotation): Position, direction: Rotate| {
+        let turned = rotation.turned(direction.clockwise_turns());
+        srs::get_kicks(piece.type_, rotation, turned).iter()
+            .map(|kick| (x+kick.0, y-kick.1, turned))                                              //KICK TABLES ARE Y UP
+            .enumerate()
+            .find(|(_, p)| !collides(*p))
+    };
+    let start = (piece.location.0, piece.location.1, piece.rotation);
+    if collides(start) {return Vec::new()}
+    //each position with how many inputs it took and the position and input that first reached it.
+    //breadth first so the first path found to anywhere is the shortest
+    let mut reached: HashMap<Position, (usize, Option<(Position, Move)>)> = HashMap::new();
+    //the first position each lock can be hard dropped onto from. the hard drop makes that soft drop free
+    let mut dropped_from: HashMap<Position, Position> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut locks = Vec::new();
+    reached.insert(start, (0, None));
+    queue.push_back(start);
+    while let Some(position) = queue.pop_front() {
+        let (x, y, rotation) = position;
+        let inputs = reached[&position].0;
+        if collides((x, y+1, rotation)) {locks.push(position)}
+        let mut next = vec!((Move::Left, (x-1, y, rotation)), (Move::Right, (x+1, y, rotation)));
+        //holding a direction slides the piece until it hits something
+        let slide = |step: isize| {
+            let mut to = x;
+            while !collides((to+step, y, rotation)) {to+=step}
+            (to, y, rotation)
+        };
+        next.push((Move::DasLeft, slide(-1)));
+        next.push((Move::DasRight, slide(1)));
+        //one soft drop can stop at any height on the way down. stopping partway is how tucks and spins under overhangs get in
+        let mut floor = y;
+        while !collides((x, floor+1, rotation)) {
+            floor+=1;
+            next.push((Move::SoftDrop((floor-y) as usize), (x, floor, rotation)));
         }
-        piece.location.0+=1;
-        //while piece in valid location
-        while !check_collision(&board.data, &piece) {
-            //drop
-            while !check_collision(&board.data, &piece) {
-                piece.location.1+=1;
-            }
-            piece.location.1-=1;
-            //add move
-            possible_moves.push(MoveData::generate_data(board.data.clone(), piece.clone(), is_held, *rotation, parameters));
-            //reset piece and move over one
-            piece.location.1 = original_location.1;
-            piece.location.0 += 1;
+        if floor != y {dropped_from.entry((x, floor, rotation)).or_insert(position);}
+        for direction in [Rotate::Clockwise, Rotate::CounterClockwise, Rotate::Half].iter() {
+            if let Some((_, kicked)) = rotate(position, *direction) {next.push((Move::Rotate(*direction), kicked))}
+        }
+        for (input, moved) in next {
+            if moved == position || reached.contains_key(&moved) || collides(moved) {continue}
+            reached.insert(moved, (inputs+1, Some((position, input))));
+            queue.push_back(moved);
         }
-        piece.location = original_location;
-        rotate_piece(&mut piece);
     }
-    possible_moves
+    let path_to = |mut position: Position| {
+        let mut path = Vec::new();
+        while let Some((_, Some((parent, input)))) = reached.get(&position) {
+            path.push(*input);
+            position = *parent;
+        }
+        path.reverse();
+        path
+    };
+    let placements = locks.into_iter().map(|position| {
+        let mut shape = shapes[position.2.index()].clone();
+        shape.location = (position.0, position.1);
+        match dropped_from.get(&position) {
+            //the hard drop moves the piece so it cant be a spin
+            Some(above) if reached[above].0 < reached[&position].0 => (shape, path_to(*above), Spin::None),
+            _ => {
+                let spin = match reached[&position].1 {
+                    Some((parent, Move::Rotate(direction))) => match rotate(parent, direction) {
+                        Some((kick, _)) => Spin::detect(&shape, direction, kick, board.width, board.height, |i| board.data[i]),
+                        None => Spin::None,
+                    },
+                    _ => Spin::None,
+                };
+                (shape, path_to(position), spin)
+            },
+        }
+    });
+    //I, S and Z cover the same cells from two rotations. only the one with the shorter path is worth keeping
+    let mut kept: Vec<(pieces::Piece, Vec<Move>, Spin)> = Vec::new();
+    let mut covering: HashMap<Vec<(isize, isize)>, usize> = HashMap::new();
+    for placement in placements {
+        match covering.get(&cells(&placement.0)) {
+            Some(i) if kept[*i].1.len() <= placement.1.len() => {},
+            Some(i) => kept[*i] = placement,
+            None => {
+                covering.insert(cells(&placement.0), kept.len());
+                kept.push(placement);
+            },
+        }
+    }
+    kept
+}
+
+///the cells a piece covers on the board
+fn cells(piece: &pieces::Piece) -> Vec<(isize, isize)> {
+    piece.data.iter().enumerate()
+        .filter(|(_, block)| **block)
+        .map(|(i, _)| (piece.location.0+(i%piece.dim) as isize, piece.location.1+(i/piece.dim) as isize))
+        .collect()
+}
+
+///the fewest inputs that lock the current piece on board over the same cells as target, not counting the hard drop.
+///anything a player presses past that is a finesse fault. None if target cant be reached
+pub fn finesse(board: &StrippedBoard, target: &pieces::Piece) -> Option<Vec<Move>> {
+    let target = cells(target);
+    find_placements(board, &board.piece).into_iter()
+        .filter(|(piece, _, _)| cells(piece) == target)
+        .map(|(_, path, _)| path)
+        .min_by_key(|path| path.len())
+}
+
+///the path the AI takes to lock the current piece at location and rotation and the points it expects for it.
+///lets the board tests check the AI predicts the same score the board gives
+#[cfg(test)]
+pub(crate) fn predict(board: &StrippedBoard, location: (isize, isize), rotation: pieces::Rotation) -> Option<(Vec<Move>, usize)> {
+    find_placements(board, &board.piece).into_iter()
+        .find(|(piece, _, _)| piece.location == location && piece.rotation == rotation)
+        .map(|(piece, path, spin)| {
+            let data = MoveData::generate_data(board.data.clone(), piece, false, path, spin, &AiParameters::default(), board);
+            (data.path, data.points_scored)
+        })
+}
+
+///counts finesse faults for a player or the AI. tell it when a piece comes out, when a movement or rotation key is pressed
+///and when the piece locks
+#[derive(Default)]
+pub struct FinesseTracker {
+    ///the board when the current piece came out
+    start: Option<StrippedBoard>,
+    ///keys pressed moving the current piece
+    presses: usize,
+    ///presses past the fewest needed, over every piece so far
+    pub faults: usize,
+    ///pieces that locked somewhere the search couldnt reach, so their faults are unknown
+    pub unchecked: usize,
+}
+
+impl FinesseTracker {
+    ///starts watching the current piece on board. board has to be from right when it came out, like the one PieceSpawned carries
+    pub fn spawned(&mut self, board: StrippedBoard) {
+        self.start = Some(board);
+        self.presses = 0;
+    }
+
+    ///counts a key press for the current piece
+    pub fn pressed(&mut self) {
+        self.presses += 1;
+    }
+
+    ///checks the piece that just locked against the fewest presses it needed. returns the faults it added,
+    ///or None if the piece wasnt watched or locked somewhere the search cant reach
+    pub fn locked(&mut self, location: (isize, isize), rotation: pieces::Rotation) -> Option<usize> {
+        let start = self.start.take()?;
+        let mut target = start.piece.get_turned((4+rotation.index()-start.piece.rotation.index())%4);
+        target.location = location;
+        match finesse(&start, &target) {
+            Some(path) => {
+                let faults = self.presses.saturating_sub(path.len());
+                self.faults += faults;
+                Some(faults)
+            },
+            None => {
+                self.unchecked += 1;
+                None
+            },
+        }
+    }
+
+    ///forgets everything for a new game
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+///get all possible moves for a piece
+fn get_moves_for_piece(board: &StrippedBoard, piece: pieces::Piece, is_held: bool, evaluator: &dyn Evaluator) -> Vec<MoveData> {
+    find_placements(board, &piece).into_iter()
+        .map(|(shape, path, spin)| MoveData::generate_data(board.data.clone(), shape, is_held, path, spin, evaluator, board))
+        .collect()
 }
 
 ///get all possible moves for current board
-fn get_possible_moves(board: &StrippedBoard, parameters: &AiParameters) -> Vec<MoveData> {
+fn get_possible_moves(board: &StrippedBoard, evaluator: &dyn Evaluator) -> Vec<MoveData> {
     let mut possible_moves = Vec::new();
-    possible_moves.extend(get_moves_for_piece(&board, board.piece.clone(), false, parameters));
+    possible_moves.extend(get_moves_for_piece(board, board.piece.clone(), false, evaluator));
     if board.piece.can_hold {
         if let Some(held) = &board.held_piece {
-            possible_moves.extend(get_moves_for_piece(&board, held.clone(), true, parameters));
-        } else {
-            possible_moves.extend(get_moves_for_piece(&board, board.next_piece.clone(), true, parameters));
+            possible_moves.extend(get_moves_for_piece(board, held.clone(), true, evaluator));
+        } else if let Some(next) = board.next_pieces.first() {
+            possible_moves.extend(get_moves_for_piece(board, next.clone(), true, evaluator));
         }
     }
     possible_moves
 }
 
 
-///takes board. gets all possible moves. finds best move. generates input
-fn get_input_move(board: StrippedBoard, parameters: &AiParameters, log_flag: bool) -> (Vec<Move>, Option<Vec<bool>>) {
-    let mut possible_moves = get_possible_moves(&board, parameters);
-    if !possible_moves.is_empty() {
-        possible_moves.sort_by(|a,b| b.value.partial_cmp(&a.value).unwrap_or(Ordering::Equal));     //IF NAN DEFAULTS TO EQUAL
-        let chosen_move = possible_moves.remove(0);
-        (chosen_move.gen_input(&board, log_flag), Some(chosen_move.board))
-    } else {(vec!(Move::Restart), None)}
+///how far ahead the AI looks before picking a move
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Search {
+    ///how many pieces to place in each sequence, using the hold and preview queue. 1 only looks at the current piece
+    pub depth: usize,
+    ///how many of the best placements at each step get searched further. 0 searches all of them
+    pub beam_width: usize,
 }
 
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            depth: 2,
+            beam_width: 8,
+        }
+    }
+}
 
+impl Search {
+    ///only looks at the current piece
+    pub const GREEDY: Self = Self {depth: 1, beam_width: 0};
+}
 
+///takes board. searches every sequence of placements. generates input for the first move of the best one
+fn get_input_move(board: StrippedBoard, evaluator: &dyn Evaluator, search: Search, log_flag: bool) -> (Vec<Move>, Option<Vec<bool>>) {
+    match best_sequence(&board, evaluator, search, search.depth.max(1)) {
+        Some((chosen_move, _, _)) => (chosen_move.gen_input(&board, evaluator, log_flag), Some(chosen_move.board)),
+        None => (vec!(Move::Restart), None),
+    }
+}
 
-
+///finds the best move on board looking depth pieces ahead. returns it with the score of the best sequence it starts
+///and how many placements that sequence has. sequences are scored by the average evaluation of their placements
+///so ones cut short by the queue can still be compared. None if there arent any moves
+fn best_sequence(board: &StrippedBoard, evaluator: &dyn Evaluator, search: Search, depth: usize) -> Option<(MoveData, f32, usize)> {
+    if board.gameover {return None}
+    let mut possible_moves = get_possible_moves(board, evaluator);
+    possible_moves.sort_by(|a,b| b.value.partial_cmp(&a.value).unwrap_or(Ordering::Equal));         //IF NAN DEFAULTS TO EQUAL
+    if depth <= 1 {
+        return possible_moves.into_iter().next().map(|m| {let value = m.value; (m, value, 1)})
+    }
+    if search.beam_width != 0 {possible_moves.truncate(search.beam_width)}
+    let mut best: Option<(MoveData, f32, usize)> = None;
+    for possible in possible_moves {
+        let (value, placed) = match possible.next_board(board) {
+            //the queue ran out so theres nothing more to look at
+            None => (possible.value, 1),
+            Some(next) => match best_sequence(&next, evaluator, search, depth-1) {
+                //tops out right after
+                None => (f32::NEG_INFINITY, 1),
+                Some((_, rest, placed)) => ((possible.value + rest*placed as f32)/(placed+1) as f32, placed+1),
+            },
+        };
+        match &best {
+            Some((_, best_value, _)) if value <= *best_value => {},
+            _ => best = Some((possible, value, placed)),
+        }
+    }
+    best
+}
 
 ///generates a log message of board mismatch
-fn log_board(last: &Vec<bool>, predicted: &Vec<bool>, board: &Vec<bool>) {
+fn log_board(last: &Vec<bool>, predicted: &Vec<bool>, board: &Vec<bool>, width: usize) {
     let mut message = String::from("Board mismatch!\n");
     let mut disp_board = |header: &str, board: &Vec<bool>| {
         message.push_str(header);
         message.push_str(":\n");
-        for row in board.chunks(BOARD_WIDTH) {
+        for row in board.chunks(width) {
             for column in row {
                 if *column {
                     message.push_str("[X]")
@@ -477,7 +928,7 @@ impl AiRadio {
 }
 
 ///for every packet received calculates moves
-fn ai_loop(radio: AiRadio, parameters: AiParameters, log_flag: bool) {
+fn ai_loop(radio: AiRadio, evaluator: impl Evaluator, search: Search, log_flag: bool) {
     let mut last_board = Vec::new();
     let mut predicted_board: Option<Vec<bool>> = None;
     for packet in &radio.rx {
@@ -486,13 +937,13 @@ fn ai_loop(radio: AiRadio, parameters: AiParameters, log_flag: bool) {
                 if log_flag {
                     if let Some(predicted) = &predicted_board {
                         if *predicted != new_board.data {
-                            log_board(&last_board, predicted, &new_board.data);
+                            log_board(&last_board, predicted, &new_board.data, new_board.width);
                         }
                     }
                 }
                 last_board = new_board.data.clone();
                 if !new_board.gameover {
-                    let result = get_input_move(new_board, &parameters, log_flag);
+                    let result = get_input_move(new_board, &evaluator, search, log_flag);
                     check!(radio.set_input(result.0));
                     predicted_board = result.1;
                 } else {check!(radio.set_input(vec!(Move::Restart)))}
@@ -501,12 +952,125 @@ fn ai_loop(radio: AiRadio, parameters: AiParameters, log_flag: bool) {
     }
 }
 
-///starts the AI thread
-pub fn start(parameters: AiParameters, log_flag: bool) -> MainRadio {
+///starts the AI thread. it picks moves with evaluator, looking as far ahead as search says
+pub fn start(evaluator: impl Evaluator + Send + 'static, search: Search, log_flag: bool) -> MainRadio {
     if log_flag {clean!("ai.log")}
     let input = Arc::new(Mutex::new(Vec::new()));
     let (tx, rx) = mpsc::channel();
     let ai_radio = AiRadio {input: Arc::clone(&input), rx};
-    let handle = thread::spawn(move || {ai_loop(ai_radio, parameters, log_flag)});
+    let handle = thread::spawn(move || {ai_loop(ai_radio, evaluator, search, log_flag)});
     MainRadio {tx, input, handle: Some(handle)}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///an empty board with the given piece spawned and the rows given top down. X is filled
+    fn board_with(type_: pieces::PieceType, rows: &[&str]) -> StrippedBoard {
+        let mut board = Board::with_settings(Settings::default(), 1).unwrap().get_board();
+        let index = pieces::PieceType::gen_piece_index();
+        board.piece = pieces::Piece::gen_piece(type_, board.piece.location, &index);
+        let top = board.height-rows.len();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                board.data[(top+y)*board.width+x] = cell == 'X';
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn finds_tucks_under_overhangs() {
+        //a 2 wide shaft with a cave off the side of it partway down
+        let board = board_with(pieces::PieceType::O, &[
+            "XXX..XXXXX", "XXX..XXXXX", "XXX..XXXXX", "XXX..XXXXX", "XXX..XXXXX",
+            "XXX..XXXXX", "XXX..XXXXX", "XXX..XXXXX", "XXX..XXXXX", "XXX..XXXXX",
+            "XXX....XXX", "XXX....XXX", "XXX..XXXXX", "XXX..XXXXX", "XXXXXXXXXX",
+            "XXXXXXXXXX", "XXXXXXXXXX", "XXXXXXXXXX",
+        ]);
+        let mut target = board.piece.clone();
+        target.location = (5, 12);
+        assert!(finesse(&board, &target).is_some());
+    }
+
+    #[test]
+    fn unreachable_locks_arent_counted_as_perfect() {
+        //a sealed off pocket the piece could only get into by teleporting
+        let board = board_with(pieces::PieceType::O, &["XXXXXXXXXX", "X..XXXXXXX", "X..XXXXXXX", "XXXXXXXXXX"]);
+        let mut tracker = FinesseTracker::default();
+        tracker.spawned(board);
+        tracker.pressed();
+        assert_eq!(tracker.locked((1, 17), pieces::Rotation::North), None);
+        assert_eq!((tracker.faults, tracker.unchecked), (0, 1));
+    }
+
+    #[test]
+    fn scores_spins() {
+        //a T-spin double slot. the T can only get in by rotating under the overhang
+        let board = board_with(pieces::PieceType::T, &["XXX.......", "XX...XXXXX", "XXX.XXXXXX"]);
+        let (_, _, spin) = find_placements(&board, &board.piece).into_iter()
+            .find(|(piece, _, _)| piece.location == (2, 17) && piece.rotation == pieces::Rotation::South)
+            .expect("the slot should be reachable");
+        assert_eq!(spin, Spin::Full);
+    }
+
+    #[test]
+    fn turns_the_short_way() {
+        let board = board_with(pieces::PieceType::T, &[]);
+        let placements = find_placements(&board, &board.piece);
+        for (rotation, direction) in [
+            (pieces::Rotation::East, Rotate::Clockwise),
+            (pieces::Rotation::West, Rotate::CounterClockwise),
+            (pieces::Rotation::South, Rotate::Half),
+        ].iter() {
+            let (_, path, spin) = placements.iter()
+                .find(|(piece, _, _)| piece.rotation == *rotation && piece.location.0 == board.piece.location.0)
+                .expect("every rotation should be reachable on an empty board");
+            assert_eq!((path.as_slice(), *spin), ([Move::Rotate(*direction)].as_ref(), Spin::None));
+        }
+    }
+
+    #[test]
+    fn spins_the_other_way_into_mirrored_slots() {
+        //the T-spin double slot from scores_spins flipped, so the T has to come in counter clockwise
+        let board = board_with(pieces::PieceType::T, &[".......XXX", "XXXXX...XX", "XXXXXX.XXX"]);
+        let (_, path, spin) = find_placements(&board, &board.piece).into_iter()
+            .find(|(piece, _, _)| piece.location == (5, 17) && piece.rotation == pieces::Rotation::South)
+            .expect("the slot should be reachable");
+        assert_eq!((path.last(), spin), (Some(&Move::Rotate(Rotate::CounterClockwise)), Spin::Full));
+    }
+
+    #[test]
+    fn the_ai_makes_no_finesse_faults() {
+        let mut board = Board::with_seed(3).unwrap();
+        let events = board.subscribe();
+        let mut tracker = FinesseTracker::default();
+        tracker.spawned(board.get_board());
+        while board.placed() < 30 && !board.gameover {
+            let (moves, _) = get_input_move(board.get_board(), &AiParameters::default(), Search::GREEDY, false);
+            for input in moves {
+                if input.is_key_press() {tracker.pressed()}
+                match input {
+                    Move::Left              => {board.move_piece(crate::game::Move::Left);},
+                    Move::Right             => {board.move_piece(crate::game::Move::Right);},
+                    Move::DasLeft           => while board.move_piece(crate::game::Move::Left) {},
+                    Move::DasRight          => while board.move_piece(crate::game::Move::Right) {},
+                    Move::SoftDrop(cells)   => for _ in 0..cells {board.move_piece(crate::game::Move::Down);},
+                    Move::Rotate(r)         => {board.rotate_piece(r);},
+                    Move::Drop              => {board.drop_piece().unwrap();},
+                    Move::Hold              => {board.hold_piece().unwrap();},
+                    Move::Restart | Move::None => {},
+                }
+                for event in events.try_iter() {
+                    match event {
+                        Event::PieceSpawned {board, ..} | Event::Hold {board, ..} => tracker.spawned(*board),
+                        Event::PieceLocked {location, rotation, type_, ..} => assert_eq!(tracker.locked(location, rotation), Some(0), "{:?} at {:?}", type_, location),
+                        _ => {},
+                    }
+                }
+            }
+        }
+        assert_eq!((board.placed(), tracker.faults, tracker.unchecked), (30, 0, 0));
+    }
+}
@@ -1,6 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 extern crate tetris;
-use std::env::args;
+use std::env::{args, var};
 
 const HELP_LOG: &str = "TetrisGAI: Why go through the work of playing tetris when you could just automate it?
 
@@ -12,12 +12,49 @@ const HELP_LOG: &str = "TetrisGAI: Why go through the work of playing tetris whe
 
 --use_best:         Use the top result from training. Stored in the top line of best.log.
 
+--mode <m>:         The game mode. One of endless, sprint (clear 40 lines), ultra (2 minutes), marathon (15 levels, 150 lines by default) or dig (clear garbage lines). Defaults to endless.
+
+--dig-lines <n>:    How many garbage lines dig mode needs cleared. Defaults to 18.
+
+--randomizer <r>:   How pieces are picked. One of bag, nes, history or random. Defaults to nes.
+
+--previews <n>:     How many upcoming pieces to show. Between 1 and 6. Defaults to 5.
+
+--size <w>x<h>:     The width and visible height of the board in blocks. At least 4x4. Defaults to 10x20.
+
+--buffer <n>:       Hidden rows above the board that pieces spawn in. Defaults to 0.
+
+--gravity <g>:      How fast pieces fall. One of nes, guideline (reaches 20G at level 18) or the path to a file of frames per cell for each level, like 48 43 38 ... 2G 20G. Defaults to nes.
+
+--lines-per-level <n>: How many lines have to be cleared for each level. Defaults to 10.
+
+--scoring <s>:      How clears are scored. One of row_height (lower rows are worth more), nes or guideline. Defaults to row_height.
+
+--lookahead <n>:    How many pieces the AI places in each sequence it searches, using the hold and next pieces. 1 only looks at the current piece. Defaults to 2.
+
+--beam <n>:         How many of the best placements at each step the AI searches past. 0 searches all of them. Defaults to 8.
+
+--das <frames>:     How long left or right has to be held before it repeats. Defaults to 10.
+
+--arr <frames>:     How many frames between each repeat once das kicks in. 0 moves straight to the wall. Defaults to 2.
+
+--sdf <factor>:     How many times faster than gravity soft drop is. 0 drops straight to the floor. Defaults to 20.
+
+--name <name>:      The name your games go on the leaderboard under. Defaults to your user name.
+
+--replay <file>:    Watch a saved replay. The last game played is always saved to replay.log.
+
 --help:             Show this command and exit.";
 
 fn main() {
-    let arguments = args().skip(1);
+    let mut arguments = args().skip(1);
     let mut settings = (false, false, false);
-    for arg in arguments {
+    let mut board_settings = tetris::Settings::default();
+    let mut replay = None;
+    let mut handling = tetris::handling::Handling::default();
+    let mut search = tetris::ai::Search::default();
+    let mut name = var("USER").or_else(|_| var("USERNAME")).unwrap_or_else(|_| String::from("PLAYER"));
+    while let Some(arg) = arguments.next() {
         match arg {
             arg if arg == "--train"     => {
                 if cfg!(debug_assertions) {settings.0 = true} 
@@ -25,10 +62,73 @@ fn main() {
             },
             arg if arg == "--auto-loop" => settings.1 = true,
             arg if arg == "--use_best"  => settings.2 = true,
+            arg if arg == "--mode" => {
+                let mode = arguments.next().expect("--mode needs a value! Try --help.");
+                board_settings.mode = mode.parse().unwrap_or_else(|e| panic!("{} Try --help.", e));
+            },
+            arg if arg == "--dig-lines" => {
+                let lines = arguments.next().expect("--dig-lines needs a value! Try --help.");
+                board_settings.dig_lines = lines.parse().unwrap_or_else(|e| panic!("Bad line count \"{}\": {} Try --help.", lines, e));
+            },
+            arg if arg == "--randomizer" => {
+                let randomizer = arguments.next().expect("--randomizer needs a value! Try --help.");
+                board_settings.randomizer = randomizer.parse().unwrap_or_else(|e| panic!("{} Try --help.", e));
+            },
+            arg if arg == "--previews" => {
+                let previews = arguments.next().expect("--previews needs a value! Try --help.");
+                board_settings.previews = previews.parse().unwrap_or_else(|e| panic!("Bad preview count \"{}\": {} Try --help.", previews, e));
+            },
+            arg if arg == "--size" => {
+                let size = arguments.next().expect("--size needs a value! Try --help.");
+                let dim = size.split('x').map(|n| n.parse::<usize>()).collect::<Vec<_>>();
+                match dim.as_slice() {
+                    [Ok(width), Ok(height)] => {board_settings.width = *width; board_settings.height = *height},
+                    _ => panic!("Bad board size \"{}\". Try --help.", size)
+                }
+            },
+            arg if arg == "--buffer" => {
+                let buffer = arguments.next().expect("--buffer needs a value! Try --help.");
+                board_settings.buffer = buffer.parse().unwrap_or_else(|e| panic!("Bad buffer size \"{}\": {} Try --help.", buffer, e));
+            },
+            arg if arg == "--gravity" => {
+                let gravity = arguments.next().expect("--gravity needs a value! Try --help.");
+                board_settings.gravity = match gravity.parse() {
+                    Ok(gravity) => gravity,
+                    Err(_) => tetris::game::Gravity::load(&gravity).unwrap_or_else(|e| panic!("Couldnt load gravity \"{}\": {} Try --help.", gravity, e)),
+                };
+            },
+            arg if arg == "--lines-per-level" => {
+                let lines = arguments.next().expect("--lines-per-level needs a value! Try --help.");
+                board_settings.lines_per_level = lines.parse().unwrap_or_else(|e| panic!("Bad line count \"{}\": {} Try --help.", lines, e));
+            },
+            arg if arg == "--scoring" => {
+                let scoring = arguments.next().expect("--scoring needs a value! Try --help.");
+                board_settings.scoring = scoring.parse().unwrap_or_else(|e| panic!("{} Try --help.", e));
+            },
+            arg if arg == "--lookahead" => {
+                let depth = arguments.next().expect("--lookahead needs a value! Try --help.");
+                search.depth = depth.parse().unwrap_or_else(|e| panic!("Bad lookahead \"{}\": {} Try --help.", depth, e));
+                if search.depth == 0 {panic!("--lookahead has to be at least 1! Try --help.")}
+            },
+            arg if arg == "--beam" => {
+                let width = arguments.next().expect("--beam needs a value! Try --help.");
+                search.beam_width = width.parse().unwrap_or_else(|e| panic!("Bad beam width \"{}\": {} Try --help.", width, e));
+            },
+            arg if arg == "--das" || arg == "--arr" || arg == "--sdf" => {
+                let frames = arguments.next().unwrap_or_else(|| panic!("{} needs a value! Try --help.", arg));
+                let frames = frames.parse().unwrap_or_else(|e| panic!("Bad value \"{}\" for {}: {} Try --help.", frames, arg, e));
+                match arg.as_str() {
+                    "--das" => handling.das = frames,
+                    "--arr" => handling.arr = frames,
+                    _       => handling.soft_drop_factor = frames,
+                }
+            },
+            arg if arg == "--name" => name = arguments.next().expect("--name needs a value! Try --help."),
+            arg if arg == "--replay" => replay = Some(arguments.next().expect("--replay needs a file! Try --help.")),
             arg if arg == "--help"      => {println!("{}",HELP_LOG); return},
             arg => panic!("Unknown argument \"{}\". Try --help",arg)
         };
     }
-    if settings.0 && (settings.1||settings.2) {panic!("--train is mutually exclusive! Try --help.")}
-    tetris::run(settings.0, settings.1, settings.2);
+    if settings.0 && (settings.1||settings.2||replay.is_some()) {panic!("--train is mutually exclusive! Try --help.")}
+    tetris::run(settings.0, settings.1, settings.2, board_settings, handling, search, replay, name);
 }
\ No newline at end of file
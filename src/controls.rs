@@ -0,0 +1,228 @@
+//! keybindings. loaded from "controls.cfg", which gets created with the defaults if it doesnt exist.
+//! each line is an action and the keys bound to it, like `left = ["A", "Left"]`, and # starts a comment.
+//! it looks like TOML but its only this one line format. theres no tables, multi line arrays or escapes,
+//! and the quotes around key names are optional
+
+use dynerr::*;
+
+use engine::game::VirtualKeyCode;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind::NotFound;
+
+///where the keybindings are stored
+const CONTROLS_FILE: &str = "controls.cfg";
+
+///builds the key name table from variant names so the names always match VirtualKeyCode
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {&[$((stringify!($key), VirtualKeyCode::$key)),*]};
+}
+
+///every key that can be bound and its name in the controls file
+const KEY_NAMES: &[(&str, VirtualKeyCode)] = key_names!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Left, Up, Right, Down,
+    Escape, Back, Return, Space, Tab, NumpadEnter,
+    LShift, RShift, LControl, RControl, LAlt, RAlt,
+    Comma, Period, Slash, Semicolon, Minus, Equals,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+);
+
+///everything a key can be bound to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Left,
+    Right,
+    SoftDrop,
+    HardDrop,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+    Hold,
+    Undo,
+    Restart,
+    ToggleAi,
+    Pause,
+}
+
+impl Action {
+    ///every action in the order theyre written to the controls file
+    pub const ALL: [Self; 12] = [
+        Self::Left, Self::Right, Self::SoftDrop, Self::HardDrop,
+        Self::RotateClockwise, Self::RotateCounterClockwise, Self::Rotate180, Self::Hold,
+        Self::Undo, Self::Restart, Self::ToggleAi, Self::Pause,
+    ];
+
+    ///the keys bound to the action when the controls file doesnt say
+    fn default_keys(&self) -> Vec<VirtualKeyCode> {
+        use VirtualKeyCode::*;
+        match self {
+            Self::Left                      => vec!(A, Left),
+            Self::Right                     => vec!(D, Right),
+            Self::SoftDrop                  => vec!(S, Down),
+            Self::HardDrop                  => vec!(Space),
+            Self::RotateClockwise           => vec!(W, R, X, Up),
+            Self::RotateCounterClockwise    => vec!(Z, Q),
+            Self::Rotate180                 => vec!(E, V),
+            Self::Hold                      => vec!(F, C),
+            Self::Undo                      => vec!(U, Back),
+            Self::Restart                   => vec!(Return, NumpadEnter),
+            Self::ToggleAi                  => vec!(P),
+            Self::Pause                     => vec!(Escape),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::Left                      => "left",
+            Self::Right                     => "right",
+            Self::SoftDrop                  => "soft_drop",
+            Self::HardDrop                  => "hard_drop",
+            Self::RotateClockwise           => "rotate_cw",
+            Self::RotateCounterClockwise    => "rotate_ccw",
+            Self::Rotate180                 => "rotate_180",
+            Self::Hold                      => "hold",
+            Self::Undo                      => "undo",
+            Self::Restart                   => "restart",
+            Self::ToggleAi                  => "toggle_ai",
+            Self::Pause                     => "pause",
+        })
+    }
+}
+
+///gets the name of a key in the controls file
+fn key_name(key: VirtualKeyCode) -> &'static str {
+    KEY_NAMES.iter().find(|(_, k)| *k == key).map_or("?", |(name, _)| name)
+}
+
+///finds a key by name. ignores case
+fn parse_key(name: &str) -> Option<VirtualKeyCode> {
+    KEY_NAMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, key)| *key)
+}
+
+///what every action is bound to
+#[derive(Clone, Debug)]
+pub struct Controls {
+    bindings: HashMap<Action, Vec<VirtualKeyCode>>,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL.iter().map(|a| (*a, a.default_keys())).collect(),
+        }
+    }
+}
+
+impl Controls {
+    ///attempts to load the controls from "controls.cfg". writes the defaults there if it doesnt exist
+    pub fn get() -> DynResult<Self> {
+        match fs::read_to_string(CONTROLS_FILE) {
+            Ok(contents) => Ok(Self::parse(&contents).map_err(|e| format!("{}: {}", CONTROLS_FILE, e))?),
+            Err(e) if e.kind() == NotFound => {
+                let controls = Self::default();
+                fs::write(CONTROLS_FILE, controls.to_string())?;
+                Ok(controls)
+            },
+            Err(e) => Err(e)?,
+        }
+    }
+
+    ///parses controls from the file format. actions that arent in it keep their default keys
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut controls = Self::default();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {continue}
+            let error = |message: String| format!("line {}: {}", i+1, message);
+            let mut pair = line.splitn(2, '=');
+            let (name, keys) = (pair.next().unwrap_or("").trim(), pair.next().ok_or_else(|| error(format!("expected \"action = [keys]\" but got \"{}\"", line)))?);
+            let action = *Action::ALL.iter()
+                .find(|a| a.to_string() == name)
+                .ok_or_else(|| error(format!("unknown action \"{}\"", name)))?;
+            let keys = keys.trim().trim_start_matches('[').trim_end_matches(']')
+                .split(',')
+                .map(|k| k.trim().trim_matches('"'))
+                .filter(|k| !k.is_empty())
+                .map(|k| parse_key(k).ok_or_else(|| error(format!("unknown key \"{}\" for {}", k, action))))
+                .collect::<Result<Vec<VirtualKeyCode>, String>>()?;
+            controls.bindings.insert(action, keys);
+        }
+        controls.validate()?;
+        Ok(controls)
+    }
+
+    ///makes sure no key is bound to more than one action
+    fn validate(&self) -> Result<(), String> {
+        let mut bound: HashMap<VirtualKeyCode, Action> = HashMap::new();
+        for action in Action::ALL.iter() {
+            for key in self.keys(*action) {
+                if let Some(other) = bound.insert(*key, *action) {
+                    if other != *action {
+                        return Err(format!("{} is bound to both {} and {}", key_name(*key), other, action))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///gets the keys bound to an action
+    pub fn keys(&self, action: Action) -> &[VirtualKeyCode] {
+        self.bindings.get(&action).map_or(&[], |keys| keys.as_slice())
+    }
+}
+
+///writes the controls in the file format
+impl fmt::Display for Controls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# action = [\"Key\", ...]. key names match winit's VirtualKeyCode, like \"A\", \"Left\", \"Space\" or \"Key1\"")?;
+        for action in Action::ALL.iter() {
+            let keys = self.keys(*action).iter().map(|k| format!("\"{}\"", key_name(*k))).collect::<Vec<String>>();
+            writeln!(f, "{} = [{}]", action, keys.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_round_trip() {
+        let controls = Controls::parse(&Controls::default().to_string()).unwrap();
+        for action in Action::ALL.iter() {
+            assert_eq!(controls.keys(*action), Action::default_keys(action).as_slice());
+        }
+    }
+
+    #[test]
+    fn parses_bindings() {
+        let controls = Controls::parse("# comment\nhold = [\"H\", Tab] # trailing comment\n\nleft = [\"j\"]").unwrap();
+        assert_eq!(controls.keys(Action::Hold), [VirtualKeyCode::H, VirtualKeyCode::Tab]);
+        assert_eq!(controls.keys(Action::Left), [VirtualKeyCode::J]);
+        //anything left out keeps its defaults
+        assert_eq!(controls.keys(Action::Pause), [VirtualKeyCode::Escape]);
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        assert_eq!(Controls::parse("\njump = [\"Space\"]").unwrap_err(), "line 2: unknown action \"jump\"");
+        assert_eq!(Controls::parse("hold = [\"Nope\"]").unwrap_err(), "line 1: unknown key \"Nope\" for hold");
+        assert!(Controls::parse("hold").unwrap_err().starts_with("line 1: expected"));
+    }
+
+    #[test]
+    fn conflicting_bindings_are_errors() {
+        //F is still held by default so it cant be given to hard drop too
+        assert_eq!(Controls::parse("hard_drop = [\"F\"]").unwrap_err(), "F is bound to both hard_drop and hold");
+        assert!(Controls::parse("hard_drop = [\"F\"]\nhold = [\"C\"]").is_ok());
+    }
+}
@@ -1,33 +1,45 @@
 pub mod pieces;
 pub mod strip;
+pub mod randomizer;
+pub mod settings;
+pub mod mode;
+pub mod garbage;
+pub mod gravity;
+pub mod scoring;
+pub mod replay;
+pub mod events;
+pub mod snapshot;
+pub mod srs;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(test)]
+mod board_tests;
 pub use strip::*;
+pub use settings::Settings;
+pub use randomizer::RandomizerType;
+pub use mode::Mode;
+pub use garbage::GarbageHoles;
+pub use gravity::Gravity;
+pub use scoring::ScoringRule;
+pub use replay::{Replay, Playback};
+pub use events::Event;
+pub use snapshot::{Snapshot, Rewind};
 
 use dynerr::*;
-use engine::sprite::Sprite;
-use engine::drawing;
 
-
-use std::fs::OpenOptions;
-use std::io::prelude::*;
 use std::mem;
-use std::convert::TryInto;
-
-use image;
-
-///width of board in blocks
-pub const BOARD_WIDTH: usize                = 10;
-///height of board in blocks
-pub const BOARD_HEIGHT: usize               = 20;
-///the left and right padding of board in blocks
-const BOARD_PAD: usize                      = 5;
-///the screen sprite
-const BOARD_SPRITE: &[u8; 7581]             = include_bytes!("sprites/board.png");
-///the location of the next piece in blocks
-const NEXT_PIECE_LOCATION: (isize, isize)   = (16,1);
-///the location of the held piece in blocks
-const HELD_PIECE_LOCATION: (isize, isize)   = (0, 1);
-///the color of gameover text
-const GAME_OVER_COLOR: [u8;4]               = [0xFF;4];
+use std::collections::VecDeque;
+use std::sync::mpsc;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+///points per combo step, multiplied by level
+pub const COMBO_POINTS: usize               = 50;
+///points per cell soft dropped
+pub const SOFT_DROP_POINTS: usize           = 1;
+///points per cell hard dropped
+pub const HARD_DROP_POINTS: usize           = 2;
 
 
 ///possible piece movements
@@ -37,83 +49,408 @@ pub enum Move {
     Left,
     Right,
 }
+
+///possible piece rotations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rotate {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
+impl Rotate {
+    ///how many clockwise quarter turns the rotation is
+    pub fn clockwise_turns(&self) -> usize {
+        match self {
+            Self::Clockwise         => 1,
+            Self::Half              => 2,
+            Self::CounterClockwise  => 3,
+        }
+    }
+}
+
+///every way a board can be played. replays are made of these
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+    Move(Move),
+    Rotate(Rotate),
+    Hold,
+    Drop,
+    ///a try_update call. gravity and lock delay
+    Update,
+    ///a tick call. the clock without gravity, used while the ai plays
+    Tick,
+}
+
+///kinds of T-spins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+impl Spin {
+    ///checks a piece that got where it is by rotating in direction with the given kick using the 3 corner rule.
+    ///filled gets the index of a cell on a board this size. walls and floor count as filled
+    pub fn detect(piece: &pieces::Piece, direction: Rotate, kick: usize, width: usize, height: usize, filled: impl Fn(usize) -> bool) -> Self {
+        if piece.type_ != pieces::PieceType::T {return Self::None}
+        //corners around the center of the T
+        let center = (piece.location.0+1, piece.location.1+1);
+        let filled = |x: isize, y: isize| {
+            if x < 0 || x >= width as isize || y >= height as isize {true}
+            else if y < 0 {false}
+            else {filled(y as usize*width+x as usize)}
+        };
+        let (top_left, top_right) = (filled(center.0-1, center.1-1), filled(center.0+1, center.1-1));
+        let (bottom_left, bottom_right) = (filled(center.0-1, center.1+1), filled(center.0+1, center.1+1));
+        if [top_left, top_right, bottom_left, bottom_right].iter().filter(|c| **c).count() < 3 {
+            return Self::None
+        }
+        //the two corners on the side the T points at
+        let front = match piece.rotation {
+            pieces::Rotation::North => top_left && top_right,
+            pieces::Rotation::East  => top_right && bottom_right,
+            pieces::Rotation::South => bottom_left && bottom_right,
+            pieces::Rotation::West  => top_left && bottom_left,
+        };
+        //the last quarter turn SRS kick always counts as a full spin. half turns have their own longer kick table
+        if front || (kick == 4 && direction != Rotate::Half) {Self::Full}
+        else {Self::Mini}
+    }
+}
+
+///what the last piece to lock did
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clear {
+    pub lines: usize,
+    pub spin: Spin,
+}
+
 pub type BoardData = Vec<Option<pieces::PieceType>>;
 ///the board object                                         SHOULD SPLIT UP INTO SEPARATE STRUCTS THAT THE BOARD CAN INTERACT WITH. LIKE "BoardPieces" AND "BoardState"
 #[derive(Clone)]
 pub struct Board {
     piece:  pieces::Piece,
     shadow: (isize, isize),
-    next_piece: pieces::PieceType,
+    next_pieces: VecDeque<pieces::PieceType>,
     held_piece: Option<pieces::Piece>,
     spawn: (isize, isize),
     data:   BoardData,
+    ///width of board in blocks
+    width: usize,
+    ///height of board in blocks including the hidden buffer
+    height: usize,
+    ///hidden rows at the top of data
+    hidden: usize,
     piece_index: pieces::PieceIndex,
-    backdrop: Sprite,
-    pub screen_dim: (usize, usize),
-    padding: usize,
     pub score: usize,
-    highscore: usize,
     cleared: usize,
     frame: usize,
     pub level: usize,
     pub gameover: bool,
+    ///true if the game ended by finishing the modes goal instead of topping out
+    pub completed: bool,
+    ///how many pieces have been locked
+    placed: usize,
+    ///the last clear or T-spin. stays until the next one happens
+    pub last_clear: Option<Clear>,
+    ///how many garbage lines have been pushed onto the board
+    garbage_added: usize,
+    ///how many garbage lines have been cleared
+    garbage_cleared: usize,
+    ///how many T-spins (minis included) have been done
+    pub tspins: usize,
+    ///how many clears in a row after the first. None if the last piece didnt clear
+    pub combo: Option<usize>,
+    ///how many tetrises and T-spin clears in a row without an easier clear between them
+    pub back_to_back: usize,
+    ///the direction and the index of the kick used if the last successful move was a rotation
+    last_kick: Option<(Rotate, usize)>,
+    ///how many frames the current piece has been on the stack
+    lock_frames: usize,
+    ///how many times the current piece has restarted its lock delay
+    lock_resets: usize,
+    ///the lowest row the current piece reached. reaching a new one gives back its lock resets
+    lowest_row: isize,
+    settings: Settings,
+    seed: u64,
+    rng: StdRng,
+    ///picks garbage holes. kept separate so garbage doesnt change the pieces
+    garbage_rng: StdRng,
+    randomizer: Box<dyn randomizer::Randomizer>,
+    subscribers: events::Subscribers,
 }
 
 impl Board {
-    ///attempts to create a new standard sized board
+    ///attempts to create a new standard sized board with a random seed
     pub fn new_board() -> DynResult<Self> {
-        let spawn = (BOARD_WIDTH as isize/2-2, 0);
-        let piece_index = pieces::PieceType::gen_piece_index();
-        let piece = pieces::Piece::gen_piece(pieces::PieceType::pick_random(), spawn, &piece_index);
-        let next_piece = {loop {
-            match pieces::PieceType::pick_random() {
-                t if t == piece.type_ => continue,
-                t => break t,
+        Self::with_seed(rand::thread_rng().gen())
+    }
+
+    ///attempts to create a new standard sized board. boards with the same seed get the same pieces
+    pub fn with_seed(seed: u64) -> DynResult<Self> {
+        Self::with_settings(Settings::default(), seed)
+    }
+
+    ///attempts to create a new board with the given settings. boards with the same settings and seed get the same pieces
+    pub fn with_settings(settings: Settings, seed: u64) -> DynResult<Self> {
+        if !(1..=settings::MAX_PREVIEWS).contains(&settings.previews) {
+            Err(format!("Previews must be between 1 and {}", settings::MAX_PREVIEWS))?
+        }
+        if settings.width < settings::MIN_SIZE || settings.height < settings::MIN_SIZE {
+            Err(format!("Board must be at least {0}x{0}", settings::MIN_SIZE))?
+        }
+        if settings.mode == Mode::Dig && settings.dig_lines == 0 {
+            Err("Dig mode needs at least 1 garbage line")?
+        }
+        if settings.lines_per_level == 0 {
+            Err("Levels need at least 1 line")?
+        }
+        if let Gravity::Custom(speeds) = &settings.gravity {
+            if speeds.is_empty() || speeds.iter().any(|s| s.frames == 0 || s.cells == 0) {
+                Err("Custom gravity needs at least 1 speed and none of them can be 0")?
             }
-        }};
-
-        let backdrop = image::load_from_memory(BOARD_SPRITE)?.to_rgba();
-        let backdrop_dim = backdrop.dimensions();
-        let backdrop = backdrop.chunks_exact(backdrop_dim.0 as usize*4).map(|r|
-            r.chunks_exact(4).map(|p|
-                p.try_into().unwrap()
-            ).collect::<Vec<[u8;4]>>()
-        ).collect::<Vec<Vec<[u8;4]>>>();
-        
+        }
+        let (width, height, hidden) = (settings.width, settings.height+settings.buffer, settings.buffer);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut randomizer = settings.randomizer.build();
+        let spawn = (width as isize/2-2, hidden.saturating_sub(2) as isize);
+        let piece_index = pieces::PieceType::gen_piece_index();
+        let piece = pieces::Piece::gen_piece(randomizer.next(&mut rng), spawn, &piece_index);
+        let next_pieces = (0..settings.previews).map(|_| randomizer.next(&mut rng)).collect();
+
         let mut board = Self {
             piece,
             shadow: spawn,
-            next_piece,
+            next_pieces,
             held_piece: None,
             spawn,
             piece_index,
-            backdrop: Sprite::add(backdrop_dim.0 as usize, backdrop_dim.1 as usize, backdrop),
-            screen_dim: (0,0),
-            padding: BOARD_PAD*pieces::BLOCK_SIZE,
-            data: vec!(None; BOARD_WIDTH*BOARD_HEIGHT),
+            data: vec!(None; width*height),
+            width,
+            height,
+            hidden,
             score: 0,
-            highscore: Self::get_highscore()?,
             cleared: 0,
             frame: 0,
             level: 0,
             gameover: false,
+            completed: false,
+            placed: 0,
+            garbage_added: 0,
+            garbage_cleared: 0,
+            last_clear: None,
+            tspins: 0,
+            combo: None,
+            back_to_back: 0,
+            last_kick: None,
+            lock_frames: 0,
+            lock_resets: 0,
+            lowest_row: spawn.1,
+            settings,
+            seed,
+            rng,
+            garbage_rng: StdRng::seed_from_u64(seed^garbage::GARBAGE_SEED_SALT),
+            randomizer,
+            subscribers: events::Subscribers::default(),
         };
-        board.screen_dim = (board.backdrop.width, board.backdrop.height);
+        if board.settings.mode == Mode::Dig {board.refill_garbage()}
         board.update_shadow();
         Ok(board)
     }
 
-    ///gets the score from "highscore.log"
-    fn get_highscore() -> DynResult<usize> {
-        let mut file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open("highscore.log")?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
-        if contents.len() == 0 {Ok(0)}
-        else {Ok(contents.parse::<usize>()?)}
+    ///gets the seed the board was created with. pass it to with_settings to replay the game
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///gets the settings the board was created with
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    ///how many frames the game has been played for
+    pub fn frames(&self) -> usize {
+        self.frame
+    }
+
+    ///how many lines have been cleared
+    pub fn lines(&self) -> usize {
+        self.cleared
+    }
+
+    ///how many frames the piece takes to fall a cell on its own. 1 when it falls more than a cell a frame
+    pub fn speed(&self) -> usize {
+        self.get_speed().frames
+    }
+
+    ///how many pieces have been placed
+    pub fn placed(&self) -> usize {
+        self.placed
+    }
+
+    ///saves everything that changes during a game so it can be restored later
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            settings: self.settings.clone(),
+            seed: self.seed,
+            piece: self.piece.clone(),
+            shadow: self.shadow,
+            next_pieces: self.next_pieces.clone(),
+            held_piece: self.held_piece.clone(),
+            data: self.data.clone(),
+            score: self.score,
+            cleared: self.cleared,
+            frame: self.frame,
+            level: self.level,
+            gameover: self.gameover,
+            completed: self.completed,
+            placed: self.placed,
+            garbage_added: self.garbage_added,
+            garbage_cleared: self.garbage_cleared,
+            last_clear: self.last_clear,
+            tspins: self.tspins,
+            combo: self.combo,
+            back_to_back: self.back_to_back,
+            last_kick: self.last_kick,
+            lock_frames: self.lock_frames,
+            lock_resets: self.lock_resets,
+            lowest_row: self.lowest_row,
+            rng: self.rng.clone(),
+            garbage_rng: self.garbage_rng.clone(),
+            randomizer: self.randomizer.clone(),
+        }
+    }
+
+    ///puts the board back to a snapshot. the snapshot has to come from a board with the same settings.
+    ///subscribers see the snapshots piece spawn again
+    pub fn restore(&mut self, snapshot: &Snapshot) -> DynResult<()> {
+        if snapshot.settings != self.settings {
+            Err("Snapshot was taken from a board with different settings")?
+        }
+        self.seed = snapshot.seed;
+        self.piece = snapshot.piece.clone();
+        self.shadow = snapshot.shadow;
+        self.next_pieces = snapshot.next_pieces.clone();
+        self.held_piece = snapshot.held_piece.clone();
+        self.data = snapshot.data.clone();
+        self.score = snapshot.score;
+        self.cleared = snapshot.cleared;
+        self.frame = snapshot.frame;
+        self.level = snapshot.level;
+        self.gameover = snapshot.gameover;
+        self.completed = snapshot.completed;
+        self.placed = snapshot.placed;
+        self.garbage_added = snapshot.garbage_added;
+        self.garbage_cleared = snapshot.garbage_cleared;
+        self.last_clear = snapshot.last_clear;
+        self.tspins = snapshot.tspins;
+        self.combo = snapshot.combo;
+        self.back_to_back = snapshot.back_to_back;
+        self.last_kick = snapshot.last_kick;
+        self.lock_frames = snapshot.lock_frames;
+        self.lock_resets = snapshot.lock_resets;
+        self.lowest_row = snapshot.lowest_row;
+        self.rng = snapshot.rng.clone();
+        self.garbage_rng = snapshot.garbage_rng.clone();
+        self.randomizer = snapshot.randomizer.clone();
+        self.emit_spawn();
+        Ok(())
+    }
+
+    ///how many garbage lines have been cleared
+    pub fn garbage_cleared(&self) -> usize {
+        self.garbage_cleared
+    }
+
+    ///pushes garbage lines in from the bottom with holes placed by holes.
+    ///blocks pushed off the top and pieces that cant move up out of the way top out. returns false if it topped out
+    pub fn add_garbage(&mut self, lines: usize, holes: GarbageHoles) -> bool {
+        if self.gameover {return false}
+        let lines = lines.min(self.height);
+        let bottom = &self.data[self.data.len()-self.width..];
+        let last = {
+            if bottom.contains(&Some(pieces::PieceType::Garbage)) {bottom.iter().position(|b| b.is_none())}
+            else {None}
+        };
+        let topped = self.data.drain(0..lines*self.width).any(|b| b.is_some());
+        for hole in garbage::gen_holes(holes, lines, self.width, last, &mut self.garbage_rng) {
+            let mut row = vec!(Some(pieces::PieceType::Garbage); self.width);
+            row[hole] = None;
+            self.data.extend(row);
+        }
+        self.garbage_added += lines;
+        //move the piece up out of the garbage
+        while self.check_collision(&self.piece, self.piece.location) {
+            if self.piece.location.1 < -(self.piece.dim as isize) {
+                self.end_game(false);
+                return false
+            }
+            self.piece.location.1 -= 1;
+        }
+        self.lowest_row = self.lowest_row.min(self.piece.location.1);
+        self.update_shadow();
+        if topped {self.end_game(false)}
+        !topped
+    }
+
+    ///tops dig mode back up to DIG_HEIGHT garbage lines until all of them have been added
+    fn refill_garbage(&mut self) {
+        let on_board = self.garbage_added-self.garbage_cleared;
+        let lines = garbage::DIG_HEIGHT.saturating_sub(on_board).min(self.settings.dig_lines.saturating_sub(self.garbage_added));
+        if lines > 0 {self.add_garbage(lines, GarbageHoles::Cheese);}
+    }
+
+    ///applies an input to the board. returns whatever the matching method returns
+    pub fn apply(&mut self, input: Input) -> DynResult<bool> {
+        match input {
+            Input::Move(direction)      => Ok(self.move_piece(direction)),
+            Input::Rotate(direction)    => Ok(self.rotate_piece(direction)),
+            Input::Hold                 => self.hold_piece(),
+            Input::Drop                 => self.drop_piece(),
+            Input::Update               => self.try_update(),
+            Input::Tick                 => Ok(self.tick()),
+        }
+    }
+
+    ///subscribes to the boards events. they keep coming through resets
+    pub fn subscribe(&mut self) -> mpsc::Receiver<Event> {
+        self.subscribers.add()
+    }
+
+    ///sends an event to every subscriber
+    fn emit(&mut self, event: Event) {
+        self.subscribers.send(event);
+    }
+
+    ///sends an event that carries a copy of the board. the copy is only made if something is listening
+    fn emit_with_board(&mut self, event: impl FnOnce(Box<StrippedBoard>) -> Event) {
+        if !self.subscribers.is_empty() {
+            let board = Box::new(self.get_board());
+            self.emit(event(board));
+        }
+    }
+
+    ///tells subscribers the current piece just came out
+    fn emit_spawn(&mut self) {
+        let type_ = self.piece.type_;
+        self.emit_with_board(|board| Event::PieceSpawned {type_, board});
+    }
+
+    ///adds points to the score
+    fn add_score(&mut self, points: usize) {
+        if points > 0 {
+            self.score += points;
+            self.emit(Event::ScoreChanged {score: self.score, gained: points});
+        }
+    }
+
+    ///ends the game. completed is true if it was won
+    fn end_game(&mut self, completed: bool) {
+        self.gameover = true;
+        self.completed = completed;
+        self.emit(Event::GameOver {completed});
     }
 
     ///attempts to hold the current piece
@@ -121,6 +458,10 @@ impl Board {
         if !self.gameover && self.piece.can_hold {
             self.piece.location = self.spawn;
             self.piece.reset_rotation(&self.piece_index);
+            self.last_kick = None;
+            self.lock_frames = 0;
+            self.lock_resets = 0;
+            self.lowest_row = self.spawn.1;
             if let Some(held) = self.held_piece.take(){
                 self.held_piece = Some(mem::replace(&mut self.piece, held));
             }
@@ -141,6 +482,9 @@ impl Board {
             }
             self.piece.can_hold = false;
             self.update_shadow();
+            if let Some(held) = self.held_piece.as_ref().map(|held| held.type_) {
+                self.emit_with_board(|board| Event::Hold {type_: held, board});
+            }
             Ok(true)
         } else {Ok(false)}
     }
@@ -148,23 +492,46 @@ impl Board {
     ///moves piece down until it gets set
     pub fn drop_piece(&mut self) -> DynResult<bool> {
         if !self.gameover {
-            while self.move_piece(Move::Down) {};
-            self.update()?;
+            let mut dropped = 0;
+            while self.shift_piece(Move::Down) {
+                dropped += 1;
+            };
+            self.add_score(dropped*self.settings.scoring.hard_drop_points());
+            self.lock_piece()?;
             Ok(true)
         } else {Ok(false)}
     }
 
-    pub fn rotate_piece(&mut self) -> bool {
-        let rotated = self.piece.get_rotated();
-        if !self.check_collision(&rotated, rotated.location) {
-            self.piece = rotated;
-            self.update_shadow();
-            true
-        } else {false}
+    ///attempts to rotate piece, trying each SRS wall kick in order. returns bool for success
+    pub fn rotate_piece(&mut self, direction: Rotate) -> bool {
+        let rotated = self.piece.get_turned(direction.clockwise_turns());
+        for (i, kick) in srs::get_kicks(rotated.type_, self.piece.rotation, rotated.rotation).iter().enumerate() {
+            let location = (rotated.location.0+kick.0, rotated.location.1-kick.1);              //KICK TABLES ARE Y UP
+            if !self.check_collision(&rotated, location) {
+                self.piece = rotated;
+                self.piece.location = location;
+                self.last_kick = Some((direction, i));
+                self.reset_lock_delay();
+                self.update_shadow();
+                return true
+            }
+        }
+        false
     }
 
     //attempts to move piece. returns bool for success
     pub fn move_piece(&mut self, direction: Move) -> bool {
+        if self.shift_piece(direction) {
+            match direction {
+                Move::Down  => self.add_score(self.settings.scoring.soft_drop_points()),
+                _           => self.reset_lock_delay(),
+            }
+            true
+        } else {false}
+    }
+
+    ///moves piece without scoring or touching the lock delay. returns bool for success
+    fn shift_piece(&mut self, direction: Move) -> bool {
         let moved = {
             match direction {
                 Move::Down  => self.piece.get_down(),
@@ -174,13 +541,36 @@ impl Board {
         };
         if !self.check_collision(&self.piece, moved) {
             self.piece.location = moved;
+            self.last_kick = None;
             if direction != Move::Down {
                 self.update_shadow();
+            } else if moved.1 > self.lowest_row {
+                self.lowest_row = moved.1;
+                self.lock_frames = 0;
+                self.lock_resets = 0;
             }
             true
         } else {false}
     }
 
+    ///restarts the lock delay of a grounded piece if it has resets left
+    fn reset_lock_delay(&mut self) {
+        if self.lock_frames > 0 {
+            match self.settings.move_resets {
+                Some(max) if self.lock_resets >= max => {},
+                _ => {
+                    self.lock_frames = 0;
+                    self.lock_resets += 1;
+                }
+            }
+        }
+    }
+
+    ///checks if the piece is sitting on something
+    fn is_grounded(&self) -> bool {
+        self.check_collision(&self.piece, self.piece.get_down())
+    }
+
     ///updates the shadow piece
     fn update_shadow(&mut self) {
         let mut shadow = self.piece.location;
@@ -194,49 +584,93 @@ impl Board {
 
     ///attempts to update. returns true if update occurred
     pub fn try_update(&mut self) -> DynResult<bool> {
-        self.frame+=1;
-        if !self.gameover
-        && self.frame%self.get_speed() == 0 {
-            self.update()?;
-            Ok(true)
-        } else {Ok(false)}
+        if !self.tick() {return Ok(false)}
+        let mut updated = false;
+        let speed = self.get_speed();
+        if self.frame.is_multiple_of(speed.frames) {
+            //fast gravity moves more than one cell a frame. stops once the piece lands so the next piece doesnt fall too
+            for _ in 0..speed.cells {
+                let grounded = self.is_grounded();
+                self.update()?;
+                if grounded {break}
+            }
+            updated = true;
+        }
+        if !self.gameover && self.settings.lock_delay > 0 {
+            if self.is_grounded() {
+                self.lock_frames += 1;
+                if self.lock_frames >= self.settings.lock_delay {
+                    self.lock_piece()?;
+                    updated = true;
+                }
+            } else {self.lock_frames = 0}
+        }
+        Ok(updated)
     }
 
-    ///gets the current frame delay based on level
-    fn get_speed(&self) -> usize {
-        match self.level {
-            0       =>  48,
-            1       =>  43,
-            2       =>  38,
-            3       =>  33,
-            4       =>  28,
-            5       =>  23,
-            6       =>  18,
-            7       =>  13,
-            8       =>  8,
-            9       =>  6,
-            10..=12 =>  5,
-            13..=15 =>  4,
-            16..=18 =>  3,
-            19..=28 =>  2,
-            _ =>        1
+    ///counts a frame toward the modes clock without doing gravity. returns false if the game is over
+    pub fn tick(&mut self) -> bool {
+        if self.gameover {return false}
+        self.frame+=1;
+        match self.settings.mode.frame_limit() {
+            Some(limit) if self.frame >= limit => self.finish(),
+            _ => {},
         }
+        !self.gameover
     }
 
-    /// does game updates
+    ///ends the game as a win
+    fn finish(&mut self) {
+        self.end_game(true);
+    }
+
+    ///gets how fast the piece falls on the current level
+    fn get_speed(&self) -> gravity::Speed {
+        self.settings.gravity.speed(self.level)
+    }
+
+    /// does game updates. moves piece down and locks it if theres no lock delay
     fn update(&mut self) -> DynResult<()> {
-        if !self.move_piece(Move::Down) {
-            self.set_piece();
-            let cleared = self.update_rows();
-            self.update_progress(cleared)?;
-            if self.data[0..BOARD_WIDTH].iter().any(|b| b.is_some())
-            || !self.next_piece() {
-                self.gameover = true;
-            }
+        if !self.shift_piece(Move::Down)
+        && self.settings.lock_delay == 0 {
+            self.lock_piece()?;
+        }
+        Ok(())
+    }
+
+    ///sets the piece, clears rows, and spawns the next piece
+    fn lock_piece(&mut self) -> DynResult<()> {
+        let spin = self.get_spin();
+        self.set_piece();
+        self.placed += 1;
+        self.emit(Event::PieceLocked {type_: self.piece.type_, location: self.piece.location, rotation: self.piece.rotation, spin});
+        let cleared = self.update_rows();
+        self.update_progress(cleared, spin)?;
+        match self.settings.mode.line_goal(self.settings.lines_per_level) {
+            Some(goal) if self.cleared >= goal => {self.finish(); return Ok(())},
+            _ => {},
+        }
+        if self.settings.mode == Mode::Dig {
+            if self.garbage_cleared >= self.settings.dig_lines {self.finish(); return Ok(())}
+            self.refill_garbage();
+            if self.gameover {return Ok(())}
+        }
+        //locking anything in the hidden rows, or the top row if there arent any, tops out
+        if self.data[0..self.width*self.hidden.max(1)].iter().any(|b| b.is_some())
+        || !self.next_piece() {
+            self.end_game(false);
         }
         Ok(())
     }
 
+    ///checks if the current piece is T-spun using the 3 corner rule
+    fn get_spin(&self) -> Spin {
+        match self.last_kick {
+            Some((direction, kick)) => Spin::detect(&self.piece, direction, kick, self.width, self.height, |i| self.data[i].is_some()),
+            None => Spin::None,
+        }
+    }
+
     ///consumes current piece and attempts to set piece
     fn set_piece(&mut self) {
         for (i, block) in self.piece.data.iter().enumerate() {
@@ -244,7 +678,7 @@ impl Board {
                 let row = i/self.piece.dim;
                 let col = i%self.piece.dim;
                 let board_index =
-                    (((self.piece.location.1+row as isize)*BOARD_WIDTH as isize)
+                    (((self.piece.location.1+row as isize)*self.width as isize)
                     + (self.piece.location.0+col as isize)) as usize;                         //IF ITS NEG IT'LL WRAP AND STILL BE INVALID
                 if let Some(board_block) = self.data.get_mut(board_index) {
                     *board_block = Some(self.piece.type_);
@@ -256,55 +690,56 @@ impl Board {
     ///checks for filled rows and removes them
     fn update_rows(&mut self) -> Vec<usize> {
         let mut cleared = Vec::new();
-        for row in 0..BOARD_HEIGHT {
-            let start_range = row*BOARD_WIDTH;
-            let end_range = start_range+BOARD_WIDTH;
+        for row in 0..self.height {
+            let start_range = row*self.width;
+            let end_range = start_range+self.width;
             if self.data[start_range..end_range].iter().all(|b| b.is_some()) {
+                if self.data[start_range..end_range].contains(&Some(pieces::PieceType::Garbage)) {self.garbage_cleared += 1}
                 self.data.drain(start_range..end_range);
-                self.data.splice(0..0, vec!(None;BOARD_WIDTH));
-                cleared.push(BOARD_HEIGHT-row);
+                self.data.splice(0..0, vec!(None;self.width));
+                cleared.push(self.height-row);
             }
         }
         cleared
     }
 
-    ///updates score on board and in file
-    fn update_progress(&mut self, cleared: Vec<usize>) -> DynResult<()> {
+    ///updates score and level
+    fn update_progress(&mut self, cleared: Vec<usize>, spin: Spin) -> DynResult<()> {
+        if !cleared.is_empty() {self.emit(Event::LinesCleared(cleared.clone()))}
         self.cleared += cleared.len();
-        self.level = self.cleared/10;
-        let modifier = match cleared.len() {
-            1 => 40,
-            2 => 100,
-            3 => 300,
-            4 => 1200,
-            _ => 3600
-        };
-        self.score += cleared.iter().map(|row|modifier*(BOARD_HEIGHT-row+1)).sum::<usize>();
-        if self.score > self.highscore {
-            self.highscore = self.score;
-            let mut file = OpenOptions::new()
-                .write(true)
-                .create(true)
-                .open("highscore.log")?;
-            file.write(format!("{}",self.highscore).as_bytes())?;
+        let level = self.cleared/self.settings.lines_per_level;
+        if level != self.level {
+            self.level = level;
+            self.emit(Event::LevelUp(level));
+        }
+        let combo = if cleared.is_empty() {None} else {Some(self.combo.map_or(0, |c| c+1))};
+        let points = self.settings.scoring.clear_points(&cleared, self.settings.height, spin, self.level, combo.unwrap_or(0), self.back_to_back);
+        if !cleared.is_empty() {
+            if scoring::is_difficult(cleared.len(), spin) {self.back_to_back += 1}
+            else {self.back_to_back = 0}
+        }
+        self.combo = combo;
+        self.add_score(points);
+        if spin != Spin::None {self.tspins += 1}
+        if spin != Spin::None || !cleared.is_empty() {
+            self.last_clear = Some(Clear {lines: cleared.len(), spin});
         }
         Ok(())
     }
 
-    //TODO compare this to the technique used in constructor method
     ///attempts to spawn next piece. returns true on success
     fn next_piece(&mut self) -> bool {
-        let next_piece = pieces::Piece::gen_piece(self.next_piece, self.spawn, &self.piece_index);
+        let next_piece = pieces::Piece::gen_piece(self.next_pieces[0], self.spawn, &self.piece_index);
         if !self.check_collision(&next_piece, self.spawn) {
-            loop {
-                let attempt_next_piece = pieces::PieceType::pick_random();
-                if attempt_next_piece != self.next_piece {
-                    self.piece = next_piece;
-                    self.next_piece = attempt_next_piece;
-                    break
-                }
-            }
+            self.piece = next_piece;
+            self.last_kick = None;
+            self.lock_frames = 0;
+            self.lock_resets = 0;
+            self.lowest_row = self.spawn.1;
+            self.next_pieces.pop_front();
+            self.next_pieces.push_back(self.randomizer.next(&mut self.rng));
             self.update_shadow();
+            self.emit_spawn();
             true
         } else {false}
     }
@@ -316,12 +751,12 @@ impl Board {
                 let row = i/piece.dim;
                 let col = i%piece.dim;
                 if (location.0+col as isize) < 0
-                || (location.0+col as isize) > BOARD_WIDTH as isize-1
+                || (location.0+col as isize) > self.width as isize-1
                 || (location.1+row as isize) < 0
-                || (location.1+row as isize) > BOARD_HEIGHT as isize-1
+                || (location.1+row as isize) > self.height as isize-1
                     {return true}
                 let board_index =
-                    (((location.1+row as isize)*BOARD_WIDTH as isize)
+                    (((location.1+row as isize)*self.width as isize)
                     + (location.0+col as isize)) as usize;                          //IF ITS NEG IT'LL WRAP AND STILL BE INVALID
                 match self.data.get(board_index) {
                     Some(Some(_)) => {return true},
@@ -333,90 +768,15 @@ impl Board {
         false
     }
 
-    ///resets board
+    ///resets board with the same settings and a new random seed. subscribers stay subscribed and see the first piece spawn
     pub fn reset(&mut self) -> DynResult<()> {
-        *self = Self::new_board()?;
+        let subscribers = mem::take(&mut self.subscribers);
+        *self = Self::with_settings(self.settings.clone(), rand::thread_rng().gen())?;
+        self.subscribers = subscribers;
+        self.emit_spawn();
         Ok(())
     }
 
-    ///draws screen during game play
-    pub fn draw(&self, screen: &mut engine::drawing::Screen){
-        screen.wipe();
-        screen.draw_sprite(&self.backdrop, (0,0));
-        //draw set blocks
-        for (i, block) in self.data.iter().enumerate() {
-            if let Some(type_) = block {
-                let row = i/BOARD_WIDTH;
-                let col = i%BOARD_WIDTH;
-                let sprite = &self.piece_index.get(&type_).unwrap().0;
-                screen.draw_sprite(sprite, (((col*sprite.width)+self.padding) as isize, (row*sprite.height) as isize))
-            }
-        }
-
-        for (i, block) in self.piece.data.iter().enumerate() {
-            if *block {
-                let sprite = &self.piece_index.get(&pieces::PieceType::Shadow).unwrap().0;
-                let row = i/self.piece.dim;
-                let col = i%self.piece.dim;
-                screen.draw_sprite(
-                    sprite,
-                    (
-                        self.shadow.0*sprite.width as isize + ((col*sprite.width)+self.padding) as isize,
-                        self.shadow.1*sprite.height as isize + (row*sprite.height) as isize
-                    )
-                )
-            }
-        }
-
-        let next_piece = self.piece_index.get(&self.next_piece).unwrap();
-        for (i, block) in next_piece.1.iter().enumerate() {
-            if *block {
-                let row = i/next_piece.2;
-                let col = i%next_piece.2;
-                screen.draw_sprite(
-                    &next_piece.0,
-                    (
-                        NEXT_PIECE_LOCATION.0*next_piece.0.width as isize + (col*next_piece.0.width) as isize,
-                        NEXT_PIECE_LOCATION.1*next_piece.0.height as isize + (row*next_piece.0.height) as isize
-                    )
-                )
-            }
-        }
-
-        let mut draw_piece = |piece: &pieces::Piece, location: (isize, isize), padding: usize, sprite: &Sprite| {
-            for (i, block) in piece.data.iter().enumerate() {
-                if *block {
-                    let row = i/piece.dim;
-                    let col = i%piece.dim;
-                    screen.draw_sprite(
-                        sprite,
-                        (
-                            location.0*sprite.width as isize + ((col*sprite.width)+padding) as isize,
-                            location.1*sprite.height as isize + (row*sprite.height) as isize
-                        )
-                    )
-                }
-            }
-        };
-        if let Some(held) = &self.held_piece {
-            let sprite = &self.piece_index.get(&held.type_).unwrap().0;
-            draw_piece(held, HELD_PIECE_LOCATION, 0, &sprite);
-        }
-        let sprite = &self.piece_index.get(&self.piece.type_).unwrap().0;
-        draw_piece(&self.piece, self.piece.location, self.padding, &sprite);
-
-        screen.draw_text((9,191), &format!("{}",self.highscore), 32.0, &[255;4], drawing::DEBUG_FONT);
-        screen.draw_text((9,254), &format!("{}",self.score), 32.0, &[255;4], drawing::DEBUG_FONT);
-        screen.draw_text((83,287), &format!("{:02}",self.level), 32.0, &[255;4], drawing::DEBUG_FONT);
-
-        if self.gameover {
-            screen.draw_text((195 ,40), "GAME OVER", 64.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
-            let message = format!("SCORE: {}",self.score);
-            screen.draw_text((225,115), &message, 32.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
-            screen.draw_text((215,200), "SPACE TO RESTART", 32.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
-        }
-    }
-
     pub fn get_board(&self) -> StrippedBoard {
         StrippedBoard::get(&self)
     }
@@ -436,15 +796,11 @@ impl Board {
 
 pub mod tests {
     use dynerr::*;
-    pub fn get_highscore() -> DynResult<usize> {
-        super::Board::get_highscore()
-    }
-
     pub fn update_shadow(board: &mut super::Board) {
         board.update_shadow();
     }
 
-    pub fn get_speed(board: &super::Board) -> usize {
+    pub fn get_speed(board: &super::Board) -> super::gravity::Speed {
         board.get_speed()
     }
 
@@ -452,6 +808,10 @@ pub mod tests {
         board.update()
     }
 
+    pub fn lock_piece(board: &mut super::Board) -> DynResult<()> {
+        board.lock_piece()
+    }
+
     pub fn set_piece(board: &mut super::Board) {
         board.set_piece()
     }
@@ -460,8 +820,12 @@ pub mod tests {
         board.update_rows()
     }
 
-    pub fn update_progress(board: &mut super::Board, cleared: Vec<usize>) -> DynResult<()> {
-        board.update_progress(cleared)
+    pub fn update_progress(board: &mut super::Board, cleared: Vec<usize>, spin: super::Spin) -> DynResult<()> {
+        board.update_progress(cleared, spin)
+    }
+
+    pub fn get_spin(board: &super::Board) -> super::Spin {
+        board.get_spin()
     }
 
     pub fn next_piece(board: &mut super::Board) -> bool {
@@ -0,0 +1,301 @@
+//! tests for the board itself. they set up its private state directly so they live in here
+
+use super::*;
+
+///a board with the given piece spawned and the rows given filling the bottom of it. X is filled
+fn board_with(settings: Settings, type_: pieces::PieceType, rows: &[&str]) -> Board {
+    let mut board = Board::with_settings(settings, 1).unwrap();
+    board.piece = pieces::Piece::gen_piece(type_, board.spawn, &board.piece_index);
+    let top = board.height-rows.len();
+    for (y, row) in rows.iter().enumerate() {
+        for (x, cell) in row.chars().enumerate() {
+            board.data[(top+y)*board.width+x] = if cell == 'X' {Some(pieces::PieceType::Garbage)} else {None};
+        }
+    }
+    board.update_shadow();
+    board
+}
+
+///the columns of the holes in each row from the top down. None for rows without exactly one hole
+fn holes(board: &Board) -> Vec<Option<usize>> {
+    board.data.chunks(board.width).map(|row| {
+        let empty = row.iter().enumerate().filter(|(_, b)| b.is_none()).map(|(x, _)| x).collect::<Vec<usize>>();
+        if empty.len() == 1 {Some(empty[0])} else {None}
+    }).collect()
+}
+
+#[test]
+fn garbage_holes() {
+    let mut board = Board::with_seed(1).unwrap();
+    assert!(board.add_garbage(3, GarbageHoles::Fixed(2)));
+    assert_eq!(holes(&board)[board.height-3..], [Some(2); 3]);
+    //fixed holes past the edge go in the last column
+    assert!(board.add_garbage(1, GarbageHoles::Fixed(100)));
+    assert_eq!(holes(&board)[board.height-1], Some(board.width-1));
+
+    let mut board = Board::with_seed(1).unwrap();
+    assert!(board.add_garbage(4, GarbageHoles::Random));
+    let random = &holes(&board)[board.height-4..];
+    assert!(random[0].is_some() && random.iter().all(|h| *h == random[0]));
+
+    let mut board = Board::with_seed(1).unwrap();
+    assert!(board.add_garbage(1, GarbageHoles::Fixed(3)));
+    assert!(board.add_garbage(8, GarbageHoles::Cheese));
+    let cheese = &holes(&board)[board.height-9..];
+    assert!(cheese.iter().all(|h| h.is_some()));
+    //no two holes line up, including the top of the batch with the line it lands on
+    assert!(cheese.windows(2).all(|pair| pair[0] != pair[1]));
+    assert_eq!(board.garbage_added, 9);
+}
+
+#[test]
+fn garbage_pushes_the_piece_up() {
+    let mut board = board_with(Settings::default(), pieces::PieceType::O, &[]);
+    while board.shift_piece(Move::Down) {}
+    let landed = board.piece.location;
+    assert!(board.add_garbage(2, GarbageHoles::Fixed(0)));
+    assert_eq!(board.piece.location, (landed.0, landed.1-2));
+    assert!(!board.check_collision(&board.piece, board.piece.location));
+    assert!(!board.gameover);
+}
+
+#[test]
+fn garbage_tops_out() {
+    let mut board = Board::with_seed(1).unwrap();
+    board.data[0] = Some(pieces::PieceType::Garbage);
+    let events = board.subscribe();
+    //the block in the top row gets pushed off the board
+    assert!(!board.add_garbage(1, GarbageHoles::Fixed(0)));
+    assert!(board.gameover && !board.completed);
+    assert!(events.try_iter().any(|e| e == Event::GameOver {completed: false}));
+    assert!(!board.add_garbage(1, GarbageHoles::Fixed(0)));
+}
+
+#[test]
+fn dig_refills_and_finishes() {
+    let settings = Settings {mode: Mode::Dig, dig_lines: 12, ..Settings::default()};
+    let mut board = Board::with_settings(settings, 1).unwrap();
+    //only DIG_HEIGHT lines go on the board at first
+    assert_eq!((board.garbage_added, board.garbage_cleared), (garbage::DIG_HEIGHT, 0));
+    assert!(holes(&board)[board.height-garbage::DIG_HEIGHT..].iter().all(|h| h.is_some()));
+    //clear the bottom line by hand. the next lock counts it and tops the garbage back up to the total
+    let bottom = board.data.len()-board.width;
+    for cell in board.data[bottom..].iter_mut() {*cell = Some(pieces::PieceType::Garbage)}
+    board.drop_piece().unwrap();
+    assert_eq!((board.garbage_added, board.garbage_cleared), (garbage::DIG_HEIGHT+1, 1));
+    assert!(!board.gameover);
+    //clearing every garbage line that was asked for finishes the game
+    board.garbage_cleared = board.settings.dig_lines;
+    board.drop_piece().unwrap();
+    assert!(board.gameover && board.completed);
+}
+
+#[test]
+fn half_turn_kicks_arent_full_spins() {
+    //a T pointing east that can only turn around with the 5th half turn kick, up 2 cells.
+    //the corners only make it a mini but the 5th quarter turn kick would make it a full spin
+    let mut board = board_with(Settings::default(), pieces::PieceType::T, &[
+        ".....X....",
+        "..........",
+        "...X.X....",
+        "...X......",
+        "..........",
+    ]);
+    board.piece = board.piece.get_turned(1);
+    board.piece.location = (3, 17);
+    assert!(board.rotate_piece(Rotate::Half));
+    assert_eq!((board.piece.location, board.last_kick), ((3, 15), Some((Rotate::Half, 4))));
+    assert_eq!(board.get_spin(), Spin::Mini);
+}
+
+#[test]
+fn t_spin_double() {
+    let settings = Settings {scoring: ScoringRule::Guideline, ..Settings::default()};
+    let mut board = board_with(settings, pieces::PieceType::T, &["XXX.......", "XX...XXXXX", "XXX.XXXXXX"]);
+    //rotate into the slot under the overhang
+    board.piece = board.piece.get_turned(1);
+    board.piece.location = (2, 17);
+    assert!(board.rotate_piece(Rotate::Clockwise));
+    assert_eq!(board.piece.location, (2, 17));
+    board.drop_piece().unwrap();
+    assert_eq!(board.last_clear, Some(Clear {lines: 2, spin: Spin::Full}));
+    assert_eq!((board.tspins, board.back_to_back, board.score), (1, 1, 1200));
+}
+
+#[test]
+fn mini_t_spin() {
+    let settings = Settings {scoring: ScoringRule::Guideline, ..Settings::default()};
+    let mut board = board_with(settings, pieces::PieceType::T, &["X.........", "..........", "X.XXXXXXXX"]);
+    //only one of the corners the T points at is filled
+    board.piece = board.piece.get_turned(1);
+    board.piece.location = (0, 17);
+    assert!(board.rotate_piece(Rotate::CounterClockwise));
+    board.drop_piece().unwrap();
+    assert_eq!(board.last_clear, Some(Clear {lines: 0, spin: Spin::Mini}));
+    assert_eq!((board.tspins, board.score), (1, 100));
+}
+
+#[test]
+fn moving_after_a_rotation_isnt_a_spin() {
+    let mut board = board_with(Settings::default(), pieces::PieceType::T, &["XXX.......", "XX...XXXXX", "XXX.XXXXXX"]);
+    board.piece.location = (3, 10);
+    assert!(board.rotate_piece(Rotate::Clockwise));
+    assert!(board.move_piece(Move::Left));
+    assert_eq!(board.get_spin(), Spin::None);
+    //turning in the air then hard dropping moves the piece after the rotation too
+    assert!(board.rotate_piece(Rotate::Clockwise));
+    board.drop_piece().unwrap();
+    assert_eq!((board.tspins, board.last_clear), (0, None));
+}
+
+///a board with an O sitting on the floor and the given lock delay
+fn grounded(lock_delay: usize, move_resets: Option<usize>) -> Board {
+    let mut board = board_with(Settings {lock_delay, move_resets, ..Settings::default()}, pieces::PieceType::O, &[]);
+    while board.shift_piece(Move::Down) {}
+    board
+}
+
+#[test]
+fn grounded_pieces_lock_after_the_delay() {
+    let mut board = grounded(5, None);
+    for _ in 0..4 {board.try_update().unwrap();}
+    assert_eq!(board.placed, 0);
+    assert!(board.try_update().unwrap());
+    assert_eq!(board.placed, 1);
+    //no lock delay locks on the next gravity step instead
+    let mut board = grounded(0, None);
+    while board.placed == 0 {board.try_update().unwrap();}
+    assert_eq!(board.frame, board.get_speed().frames);
+}
+
+#[test]
+fn moves_reset_the_lock_delay_until_they_run_out() {
+    let mut board = grounded(5, Some(2));
+    for direction in [Move::Left, Move::Right, Move::Left].iter() {
+        for _ in 0..3 {board.try_update().unwrap();}
+        assert!(board.move_piece(*direction));
+    }
+    //the first two moves gave back 3 frames each but the last one didnt
+    assert_eq!((board.lock_frames, board.lock_resets), (3, 2));
+    board.try_update().unwrap();
+    assert_eq!(board.placed, 0);
+    board.try_update().unwrap();
+    assert_eq!(board.placed, 1);
+}
+
+#[test]
+fn reaching_a_new_row_gives_resets_back() {
+    let settings = Settings {lock_delay: 5, move_resets: Some(2), ..Settings::default()};
+    let mut board = board_with(settings, pieces::PieceType::O, &["XXXXX....."]);
+    while board.shift_piece(Move::Down) {}
+    board.lock_frames = 3;
+    board.lock_resets = 2;
+    //sliding off the ledge doesnt reset anything but falling off it does
+    while board.move_piece(Move::Right) {}
+    assert_eq!((board.lock_frames, board.lock_resets), (3, 2));
+    assert!(board.move_piece(Move::Down));
+    assert_eq!((board.lock_frames, board.lock_resets), (0, 0));
+}
+
+#[test]
+fn drops_score_per_cell() {
+    let mut board = board_with(Settings {scoring: ScoringRule::Guideline, ..Settings::default()}, pieces::PieceType::O, &[]);
+    for _ in 0..3 {assert!(board.move_piece(Move::Down));}
+    assert_eq!(board.score, 3*SOFT_DROP_POINTS);
+    let cells = (board.shadow.1-board.piece.location.1) as usize;
+    board.drop_piece().unwrap();
+    assert_eq!(board.score, 3*SOFT_DROP_POINTS+cells*HARD_DROP_POINTS);
+    //the NES didnt have hard drop
+    let mut board = board_with(Settings {scoring: ScoringRule::Nes, ..Settings::default()}, pieces::PieceType::O, &[]);
+    board.drop_piece().unwrap();
+    assert_eq!(board.score, 0);
+}
+
+#[test]
+fn event_order() {
+    let settings = Settings {scoring: ScoringRule::Guideline, ..Settings::default()};
+    let mut board = board_with(settings, pieces::PieceType::O, &["XXXXXXXX..", "XXXXXXXX.."]);
+    let events = board.subscribe();
+    board.piece.location = (8, 0);
+    board.drop_piece().unwrap();
+    let next = Event::PieceSpawned {type_: board.piece.type_, board: Box::new(board.get_board())};
+    assert_eq!(events.try_iter().collect::<Vec<Event>>(), vec!(
+        Event::ScoreChanged {score: 36, gained: 36},
+        Event::PieceLocked {type_: pieces::PieceType::O, location: (8, 18), rotation: pieces::Rotation::North, spin: Spin::None},
+        Event::LinesCleared(vec!(2, 1)),
+        Event::ScoreChanged {score: 336, gained: 300},
+        next,
+    ));
+    //locking in the top row tops out
+    board.piece.location.1 = -1;
+    board.lock_piece().unwrap();
+    assert!(events.try_iter().any(|e| e == Event::GameOver {completed: false}));
+    //subscribers keep listening through a reset and see its first piece
+    board.reset().unwrap();
+    assert_eq!(events.try_iter().collect::<Vec<Event>>(), vec!(Event::PieceSpawned {type_: board.piece.type_, board: Box::new(board.get_board())}));
+}
+
+///locks the current piece where the AI would put it and checks the board scores it the same as the AI predicted
+fn score_matches_ai(board: &mut Board, location: (isize, isize), rotation: pieces::Rotation) {
+    let (path, predicted) = crate::ai::predict(&board.get_board(), location, rotation).expect("the placement should be reachable");
+    for input in path {
+        match input {
+            crate::ai::Move::Left           => {board.shift_piece(Move::Left);},
+            crate::ai::Move::Right          => {board.shift_piece(Move::Right);},
+            crate::ai::Move::DasLeft        => while board.shift_piece(Move::Left) {},
+            crate::ai::Move::DasRight       => while board.shift_piece(Move::Right) {},
+            crate::ai::Move::SoftDrop(cells)=> for _ in 0..cells {board.shift_piece(Move::Down);},
+            crate::ai::Move::Rotate(r)      => {board.rotate_piece(r);},
+            _ => {},
+        }
+    }
+    while board.shift_piece(Move::Down) {}
+    assert_eq!((board.piece.location, board.piece.rotation), (location, rotation));
+    let before = board.score;
+    board.lock_piece().unwrap();
+    assert_eq!(board.score-before, predicted, "{:?} {:?}", board.settings.scoring, board.last_clear);
+}
+
+#[test]
+fn the_ai_predicts_the_points_the_board_gives() {
+    for scoring in ScoringRule::ALL.iter().copied() {
+        //a buffer so the hidden rows have to be left out of the row heights, and each clear levels up
+        let settings = Settings {scoring, buffer: 2, ..Settings::default()};
+        let level_up = settings.lines_per_level-1;
+
+        //a tetris on the back of a difficult clear and partway through a combo
+        let mut board = board_with(settings.clone(), pieces::PieceType::I, &["XXXXXXXXX."; 4]);
+        board.back_to_back = 1;
+        board.combo = Some(1);
+        board.cleared = level_up;
+        score_matches_ai(&mut board, (7, 18), pieces::Rotation::East);
+
+        //a T-spin double keeping the back to back going
+        let mut board = board_with(settings.clone(), pieces::PieceType::T, &["XXX.......", "XX...XXXXX", "XXX.XXXXXX"]);
+        board.back_to_back = 2;
+        board.cleared = level_up;
+        score_matches_ai(&mut board, (2, 19), pieces::Rotation::South);
+
+        //a double that breaks the back to back but continues a combo
+        let mut board = board_with(settings.clone(), pieces::PieceType::O, &["XXXXXXXX..", "XXXXXXXX.."]);
+        board.back_to_back = 3;
+        board.combo = Some(4);
+        score_matches_ai(&mut board, (8, 20), pieces::Rotation::North);
+
+        //a T-spin with no lines
+        let mut board = board_with(settings, pieces::PieceType::T, &["XXX.......", "XX...X....", "XXX.XXXXX."]);
+        score_matches_ai(&mut board, (2, 19), pieces::Rotation::South);
+    }
+}
+
+#[test]
+fn same_seed_same_game() {
+    for randomizer in RandomizerType::ALL.iter().copied() {
+        let settings = Settings {randomizer, ..Settings::default()};
+        let mut boards = [Board::with_settings(settings.clone(), 7).unwrap(), Board::with_settings(settings, 7).unwrap()];
+        for _ in 0..20 {
+            for board in boards.iter_mut() {board.drop_piece().unwrap();}
+            assert_eq!(boards[0].get_board(), boards[1].get_board());
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use super::{Spin, StrippedBoard, pieces::{PieceType, Rotation}};
+
+use std::sync::mpsc;
+
+///something that happened on a board. sent to everything subscribed to it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    ///a new piece came out of the queue. also sent when a reset or restore puts a new current piece on the board.
+    ///board is the board right as it came out, before anything moved it
+    PieceSpawned {type_: PieceType, board: Box<StrippedBoard>},
+    ///a piece was set on the stack
+    PieceLocked {
+        type_: PieceType,
+        location: (isize, isize),
+        rotation: Rotation,
+        spin: Spin,
+    },
+    ///rows were cleared. each row is its height from the bottom before clearing, same as update_rows
+    LinesCleared(Vec<usize>),
+    ///the level went up. holds the new level
+    LevelUp(usize),
+    ///a piece was put in hold. board is the board right as the piece that replaced it came out
+    Hold {type_: PieceType, board: Box<StrippedBoard>},
+    ///the game ended. completed is true if the modes goal was finished instead of topping out
+    GameOver {completed: bool},
+    ///points were scored
+    ScoreChanged {score: usize, gained: usize},
+}
+
+///everything listening to a board. cloning a board doesnt clone its subscribers so copies made for
+///lookahead and benchmarks stay quiet
+#[derive(Default)]
+pub struct Subscribers(Vec<mpsc::Sender<Event>>);
+
+impl Subscribers {
+    ///adds a subscriber and returns the end it listens on
+    pub fn add(&mut self) -> mpsc::Receiver<Event> {
+        let (tx, rx) = mpsc::channel();
+        self.0.push(tx);
+        rx
+    }
+
+    ///true if nothing is listening
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    ///sends an event to every subscriber. ones that hung up get dropped
+    pub fn send(&mut self, event: Event) {
+        if self.0.is_empty() {return}
+        self.0.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
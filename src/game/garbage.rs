@@ -0,0 +1,38 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+
+///mixed into the boards seed so garbage holes dont share an rng with the pieces
+pub const GARBAGE_SEED_SALT: u64 = 0x6A7B_A6E5;
+///the most garbage lines dig mode keeps on the board at once
+pub const DIG_HEIGHT: usize = 10;
+
+///where the holes go in a batch of garbage lines
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GarbageHoles {
+    ///every line in the batch gets the hole in the same random column
+    Random,
+    ///every line gets the hole in this column
+    Fixed(usize),
+    ///every line gets its own random hole that never lines up with the one above it
+    Cheese,
+}
+
+///picks the hole column for each garbage line from the top line of the batch down.
+///last is the hole of the boards bottom line, which ends up sitting on the batch
+pub fn gen_holes(holes: GarbageHoles, lines: usize, width: usize, last: Option<usize>, rng: &mut StdRng) -> Vec<usize> {
+    match holes {
+        GarbageHoles::Random    => vec!(rng.gen_range(0, width); lines),
+        GarbageHoles::Fixed(col) => vec!(col.min(width-1); lines),
+        GarbageHoles::Cheese    => {
+            let mut columns = Vec::with_capacity(lines);
+            let mut above = last;
+            for _ in 0..lines {
+                let mut col = rng.gen_range(0, width);
+                if Some(col) == above {col = (col+rng.gen_range(1, width))%width}
+                columns.push(col);
+                above = Some(col);
+            }
+            columns
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use dynerr::*;
+
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+///how many lines have to be cleared for each level by default
+pub const LINES_PER_LEVEL: usize = 10;
+///20G. sends the piece straight to the floor on a standard board
+pub const MAX_G: usize = 20;
+
+///how fast a piece falls. moves cells down once every frames frames
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Speed {
+    pub frames: usize,
+    pub cells: usize,
+}
+
+impl Speed {
+    ///falls a cell every n frames
+    pub const fn frames(frames: usize) -> Self {
+        Self {frames, cells: 1}
+    }
+
+    ///falls n cells every frame
+    pub const fn g(cells: usize) -> Self {
+        Self {frames: 1, cells}
+    }
+}
+
+///written as the frames per cell, like "48", or the cells per frame followed by G, like "20G"
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cells {
+            1 => write!(f, "{}", self.frames),
+            cells => write!(f, "{}G", cells),
+        }
+    }
+}
+
+impl FromStr for Speed {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_speed = |e: &dyn fmt::Display| format!("Bad speed \"{}\": {}", s, e);
+        let speed = match s.strip_suffix('G').or_else(|| s.strip_suffix('g')) {
+            Some(cells) => Self::g(cells.parse().map_err(|e| bad_speed(&e))?),
+            None        => Self::frames(s.parse().map_err(|e| bad_speed(&e))?),
+        };
+        if speed.frames == 0 || speed.cells == 0 {
+            Err(bad_speed(&"speeds cant be 0"))?
+        }
+        Ok(speed)
+    }
+}
+
+///gets the NES frames per cell for a level
+fn nes_frames(level: usize) -> usize {
+    match level {
+        0       =>  48,
+        1       =>  43,
+        2       =>  38,
+        3       =>  33,
+        4       =>  28,
+        5       =>  23,
+        6       =>  18,
+        7       =>  13,
+        8       =>  8,
+        9       =>  6,
+        10..=12 =>  5,
+        13..=15 =>  4,
+        16..=18 =>  3,
+        19..=28 =>  2,
+        _ =>        1
+    }
+}
+
+///the guideline curve of (0.8-(level*0.007))^level seconds per cell rounded to whole frames or cells.
+///every level past the end is 20G
+const GUIDELINE_SPEEDS: [Speed; 18] = [
+    Speed::frames(60), Speed::frames(48), Speed::frames(37), Speed::frames(28), Speed::frames(21), Speed::frames(16),
+    Speed::frames(11), Speed::frames(8), Speed::frames(6), Speed::frames(4), Speed::frames(3), Speed::frames(2),
+    Speed::frames(1), Speed::g(1), Speed::g(2), Speed::g(4), Speed::g(7), Speed::g(11),
+];
+
+///how fast pieces fall at each level
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gravity {
+    ///the NES frame table
+    Nes,
+    ///the tetris guideline curve. hits 20G at level 18
+    Guideline,
+    ///a speed for each level starting at 0. the last one is used for every level after it
+    Custom(Vec<Speed>),
+}
+
+impl Gravity {
+    ///gets the speed pieces fall at on level
+    pub fn speed(&self, level: usize) -> Speed {
+        match self {
+            Self::Nes       => Speed::frames(nes_frames(level)),
+            Self::Guideline => *GUIDELINE_SPEEDS.get(level).unwrap_or(&Speed::g(MAX_G)),
+            Self::Custom(speeds) => match speeds.get(level).or_else(|| speeds.last()) {
+                Some(speed) => *speed,
+                None        => Speed::frames(1),
+            },
+        }
+    }
+
+    ///attempts to load a custom table from a file. speeds are split by whitespace or commas and # starts a comment
+    pub fn load(path: &str) -> DynResult<Self> {
+        let contents = fs::read_to_string(path)?;
+        let table = contents.lines().map(|l| l.split('#').next().unwrap_or("")).collect::<Vec<&str>>().join(",");
+        Ok(parse_table(&table).map_err(|e| format!("{}: {}", path, e))?)
+    }
+}
+
+///parses a list of speeds split by whitespace or commas into a custom table
+fn parse_table(table: &str) -> Result<Gravity, String> {
+    let speeds = table.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse())
+        .collect::<Result<Vec<Speed>, String>>()?;
+    if speeds.is_empty() {Err("A custom gravity table needs at least 1 speed")?}
+    Ok(Gravity::Custom(speeds))
+}
+
+///custom tables are written inline so replays dont depend on the file still being there
+impl fmt::Display for Gravity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nes       => write!(f, "nes"),
+            Self::Guideline => write!(f, "guideline"),
+            Self::Custom(speeds) => write!(f, "custom:{}", speeds.iter().map(|s| s.to_string()).collect::<Vec<String>>().join(",")),
+        }
+    }
+}
+
+impl FromStr for Gravity {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "nes"       => Ok(Self::Nes),
+            "guideline" => Ok(Self::Guideline),
+            s => match s.strip_prefix("custom:") {
+                Some(table) => parse_table(table),
+                None        => Err(format!("Unknown gravity \"{}\". Expected nes, guideline or custom:<speeds>", s)),
+            },
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+use std::str::FromStr;
+
+///how many frames the game runs per second. used to turn frame counts into times
+pub const FRAMES_PER_SECOND: usize = 60;
+///how many lines a sprint needs
+pub const SPRINT_LINES: usize = 40;
+///how long an ultra game lasts in frames. 2 minutes
+pub const ULTRA_FRAMES: usize = 2*60*FRAMES_PER_SECOND;
+///how many levels a marathon lasts. its line goal is this many levels worth of lines
+pub const MARATHON_LEVELS: usize = 15;
+///how many garbage lines a dig game needs by default
+pub const DIG_LINES: usize = 18;
+
+///the game modes a board can be played in. each one has its own way of ending
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    ///the original mode. keeps going until you top out
+    Endless,
+    ///clear 40 lines as fast as possible
+    Sprint,
+    ///score as much as possible in 2 minutes
+    Ultra,
+    ///clear 15 levels worth of lines. 150 at the default 10 lines per level
+    Marathon,
+    ///dig through a set number of garbage lines as fast as possible
+    Dig,
+}
+
+impl Mode {
+    ///every mode. used for menus and parsing
+    pub const ALL: [Self; 5] = [Self::Endless, Self::Sprint, Self::Ultra, Self::Marathon, Self::Dig];
+
+    ///how many lines end the game if the mode has a line goal. marathons go by how often the level goes up
+    pub fn line_goal(&self, lines_per_level: usize) -> Option<usize> {
+        match self {
+            Self::Sprint    => Some(SPRINT_LINES),
+            Self::Marathon  => Some(MARATHON_LEVELS*lines_per_level),
+            _               => None,
+        }
+    }
+
+    ///how many frames the game lasts if the mode is timed
+    pub fn frame_limit(&self) -> Option<usize> {
+        match self {
+            Self::Ultra => Some(ULTRA_FRAMES),
+            _           => None,
+        }
+    }
+
+    ///true if personal bests are times instead of scores
+    pub fn is_timed(&self) -> bool {
+        *self == Self::Sprint || *self == Self::Dig
+    }
+
+    ///true if result beats best for this mode
+    pub fn is_better(&self, result: usize, best: usize) -> bool {
+        if self.is_timed() {result < best}
+        else {result > best}
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::Endless   => "endless",
+            Self::Sprint    => "sprint",
+            Self::Ultra     => "ultra",
+            Self::Marathon  => "marathon",
+            Self::Dig       => "dig",
+        })
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter()
+            .find(|m| m.to_string() == s.to_lowercase())
+            .copied()
+            .ok_or(format!("Unknown mode \"{}\"", s))
+    }
+}
+
+///formats a frame count as a time like "1:23.45"
+pub fn format_frames(frames: usize) -> String {
+    let centis = frames*100/FRAMES_PER_SECOND;
+    format!("{}:{:02}.{:02}", centis/6000, centis/100%60, centis%100)
+}
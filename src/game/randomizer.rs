@@ -0,0 +1,226 @@
+use super::pieces::PieceType;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+///every piece a randomizer can pick from
+const PIECES: [PieceType; 7] = [PieceType::I, PieceType::J, PieceType::L, PieceType::O, PieceType::T, PieceType::S, PieceType::Z];
+///how many times the history randomizer rerolls before giving up
+const HISTORY_ROLLS: usize = 6;
+
+///decides the order pieces come in. gets the boards rng so games stay reproducible from the seed
+pub trait Randomizer: Send {
+    ///gets the next piece in the sequence
+    fn next(&mut self, rng: &mut StdRng) -> PieceType;
+    ///clones into a box so boards can still be cloned
+    fn box_clone(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+///the randomizers a board can be built with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RandomizerType {
+    Bag,
+    Nes,
+    History,
+    Random,
+}
+
+impl RandomizerType {
+    ///every randomizer type. used for menus and parsing
+    pub const ALL: [Self; 4] = [Self::Bag, Self::Nes, Self::History, Self::Random];
+
+    ///creates a fresh randomizer of this type
+    pub fn build(&self) -> Box<dyn Randomizer> {
+        match self {
+            Self::Bag     => Box::new(Bag::default()),
+            Self::Nes     => Box::new(Nes::default()),
+            Self::History => Box::new(History::default()),
+            Self::Random  => Box::new(Uniform),
+        }
+    }
+}
+
+impl fmt::Display for RandomizerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::Bag     => "bag",
+            Self::Nes     => "nes",
+            Self::History => "history",
+            Self::Random  => "random",
+        })
+    }
+}
+
+impl FromStr for RandomizerType {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter()
+            .find(|r| r.to_string() == s.to_lowercase())
+            .copied()
+            .ok_or(format!("Unknown randomizer \"{}\"", s))
+    }
+}
+
+
+
+///guideline 7-bag. shuffles all 7 pieces and deals them out before refilling
+#[derive(Clone, Default)]
+pub struct Bag {
+    bag: Vec<PieceType>,
+}
+
+impl Randomizer for Bag {
+    fn next(&mut self, rng: &mut StdRng) -> PieceType {
+        if self.bag.is_empty() {
+            self.bag.extend_from_slice(&PIECES);
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap()
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+
+
+///NES randomizer. rolls an 8 sided die and rerolls once if it gets the last piece or the 8th side
+#[derive(Clone, Default)]
+pub struct Nes {
+    last: Option<PieceType>,
+}
+
+impl Randomizer for Nes {
+    fn next(&mut self, rng: &mut StdRng) -> PieceType {
+        let roll = rng.gen_range(0, PIECES.len()+1);
+        let piece = match PIECES.get(roll) {
+            Some(piece) if Some(*piece) != self.last => *piece,
+            _ => PIECES[rng.gen_range(0, PIECES.len())],
+        };
+        self.last = Some(piece);
+        piece
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+
+
+///TGM style randomizer. rerolls a few times to avoid the last 4 pieces
+#[derive(Clone)]
+pub struct History {
+    history: VecDeque<PieceType>,
+    first: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            history: vec!(PieceType::Z, PieceType::S, PieceType::S, PieceType::Z).into(),
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for History {
+    fn next(&mut self, rng: &mut StdRng) -> PieceType {
+        let piece = if self.first {
+            //never start on a piece that forces an overhang
+            self.first = false;
+            *[PieceType::I, PieceType::J, PieceType::L, PieceType::T].choose(rng).unwrap()
+        } else {
+            let mut piece = PIECES[rng.gen_range(0, PIECES.len())];
+            for _ in 1..HISTORY_ROLLS {
+                if !self.history.contains(&piece) {break}
+                piece = PIECES[rng.gen_range(0, PIECES.len())];
+            }
+            piece
+        };
+        self.history.pop_front();
+        self.history.push_back(piece);
+        piece
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+
+
+///every piece has the same chance every time
+#[derive(Clone)]
+pub struct Uniform;
+
+impl Randomizer for Uniform {
+    fn next(&mut self, rng: &mut StdRng) -> PieceType {
+        PieceType::pick_random(rng)
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    ///the first count pieces a fresh randomizer of type_ deals from seed
+    fn deal(type_: RandomizerType, seed: u64, count: usize) -> Vec<PieceType> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut randomizer = type_.build();
+        (0..count).map(|_| randomizer.next(&mut rng)).collect()
+    }
+
+    #[test]
+    fn bags_deal_every_piece_once() {
+        for bag in deal(RandomizerType::Bag, 1, 7*100).chunks(7) {
+            assert!(PIECES.iter().all(|piece| bag.contains(piece)), "{:?}", bag);
+        }
+    }
+
+    #[test]
+    fn history_avoids_recent_pieces() {
+        for seed in 0..50 {
+            let first = deal(RandomizerType::History, seed, 1)[0];
+            assert!([PieceType::I, PieceType::J, PieceType::L, PieceType::T].contains(&first));
+        }
+        //every reroll has to land in the history for a repeat so theyre rare. (4/7)^6 at most
+        let pieces = deal(RandomizerType::History, 1, 7000);
+        let repeats = pieces.windows(5).filter(|w| w[..4].contains(&w[4])).count();
+        assert!(repeats < pieces.len()/20, "{} repeats", repeats);
+    }
+
+    #[test]
+    fn nes_rerolls_repeats() {
+        //a repeat needs the first roll to be the last piece or the 8th side then the reroll to land on it. 2/8*1/7
+        let pieces = deal(RandomizerType::Nes, 1, 7000);
+        let repeats = pieces.windows(2).filter(|w| w[0] == w[1]).count();
+        assert!(repeats > 0 && repeats < pieces.len()/14, "{} repeats", repeats);
+        assert!(PIECES.iter().all(|piece| pieces.contains(piece)));
+    }
+
+    #[test]
+    fn same_seed_same_pieces() {
+        for type_ in RandomizerType::ALL.iter().copied() {
+            assert_eq!(deal(type_, 42, 100), deal(type_, 42, 100), "{}", type_);
+            assert_ne!(deal(type_, 42, 100), deal(type_, 43, 100), "{}", type_);
+        }
+    }
+}
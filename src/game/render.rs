@@ -0,0 +1,319 @@
+use super::*;
+
+use engine::sprite::Sprite;
+use engine::drawing;
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use image;
+
+///the size of each block. used to calc grid
+pub const BLOCK_SIZE:       usize           = 32;
+///the thickness of piece border in pixels
+const BORDER_SIZE:          usize           = 2;
+///the color of piece borders
+const BORDER_COLOR:         [u8;4]          = [0x00, 0x00, 0x00, 0xFF];
+///the color of shadow
+const SHADOW_COLOR:         [u8;4]          = [0x00;4];
+///the color of the shadows border
+const SHADOW_BORDER_COLOR:  [u8;4]          = [0xDC, 0xDC, 0xDC, 0xFF];
+///the left and right padding of board in blocks
+const BOARD_PAD: usize                      = 5;
+///the screen sprite
+const BOARD_SPRITE: &[u8; 7581]             = include_bytes!("../sprites/board.png");
+///the width of each side panel of the backdrop in pixels
+const PANEL_WIDTH: usize                    = BOARD_PAD*BLOCK_SIZE;
+///the pixel location of an empty grid cell in the backdrop. tiled to draw boards of any size
+const CELL_LOCATION: (usize, usize)         = (192, 32);
+///the location of the next piece in blocks from the right panel
+const NEXT_PIECE_LOCATION: (isize, isize)   = (1,1);
+///the location of the held piece in blocks
+const HELD_PIECE_LOCATION: (isize, isize)   = (0, 1);
+///the size of each block in the preview queue
+const PREVIEW_BLOCK_SIZE: usize             = 16;
+///the pixel location of the top of the preview queue under the next piece from the right panel
+const PREVIEW_LOCATION: (isize, isize)      = (32, 168);
+///the width of the preview queue in pixels
+const PREVIEW_WIDTH: isize                  = 128;
+///the vertical space each piece in the preview queue gets in pixels
+const PREVIEW_SPACING: isize                = 46;
+///the color of gameover text
+const GAME_OVER_COLOR: [u8;4]               = [0xFF;4];
+///the location of the last clear text
+const CLEAR_LOCATION: (isize, isize)        = (9, 352);
+///the location of the combo and back to back text
+const COMBO_LOCATION: (isize, isize)        = (9, 370);
+///the location of the finesse fault count
+const FAULTS_LOCATION: (isize, isize)       = (9, 388);
+///the location of the mode name from the right panel
+const MODE_LOCATION: (isize, isize)         = (9, 606);
+///the location of the modes goal and clock from the right panel
+const GOAL_LOCATION: (isize, isize)         = (9, 622);
+
+const I_COLOR: [u8;4] = [0x00, 0xFF, 0xFF, 0xFF];
+const O_COLOR: [u8;4] = [0xFF, 0xFF, 0x00, 0xFF];
+const T_COLOR: [u8;4] = [0x80, 0x00, 0x80, 0xFF];
+const S_COLOR: [u8;4] = [0x00, 0x80, 0x00, 0xFF];
+const Z_COLOR: [u8;4] = [0xFF, 0x00, 0x00, 0xFF];
+const J_COLOR: [u8;4] = [0x00, 0x00, 0xFF, 0xFF];
+const L_COLOR: [u8;4] = [0xFF, 0xA5, 0x00, 0xFF];
+const GARBAGE_COLOR: [u8;4] = [0x80, 0x80, 0x80, 0xFF];
+
+///list of piece sprites
+type SpriteIndex = HashMap<pieces::PieceType, Sprite>;
+
+//only run during renderer creation so i didnt bother benchmarking
+//TODO instead of iterating through entire 2d vec, convert top and bottom rows, then convert left and right portions of rows. maybe even do in 1D via chunking? pound out the first and last rows as one slice then the middle rows do chunking over its slice
+///generates a colored block with a border
+fn gen_block(color: [u8;4], border: [u8;4], size: usize) -> Sprite {
+    let mut block = vec!(vec!(color; size); size);
+    for row_i in 0..size {
+        for pixel_i in 0..size {
+            if (0..BORDER_SIZE).contains(&row_i)
+            || (0..BORDER_SIZE).contains(&pixel_i)
+            || (size-BORDER_SIZE..size).contains(&row_i)
+            || (size-BORDER_SIZE..size).contains(&pixel_i) {
+                block[row_i][pixel_i] = border;
+            }
+        }
+    }
+    Sprite::add(size, size, block)
+}
+
+///generates hashmap index of piece sprites with the given block size
+fn gen_sprite_index(size: usize) -> SpriteIndex {
+    let mut index = HashMap::new();
+    index.insert(pieces::PieceType::I,      gen_block(I_COLOR, BORDER_COLOR, size));
+    index.insert(pieces::PieceType::J,      gen_block(J_COLOR, BORDER_COLOR, size));
+    index.insert(pieces::PieceType::L,      gen_block(L_COLOR, BORDER_COLOR, size));
+    index.insert(pieces::PieceType::O,      gen_block(O_COLOR, BORDER_COLOR, size));
+    index.insert(pieces::PieceType::T,      gen_block(T_COLOR, BORDER_COLOR, size));
+    index.insert(pieces::PieceType::S,      gen_block(S_COLOR, BORDER_COLOR, size));
+    index.insert(pieces::PieceType::Z,      gen_block(Z_COLOR, BORDER_COLOR, size));
+    index.insert(pieces::PieceType::Shadow, gen_block(SHADOW_COLOR, SHADOW_BORDER_COLOR, size));
+    index.insert(pieces::PieceType::Garbage, gen_block(GARBAGE_COLOR, BORDER_COLOR, size));
+    index
+}
+
+///copies a rectangle of pixels out of an image into a sprite
+fn crop(image: &[Vec<[u8;4]>], location: (usize, usize), dim: (usize, usize)) -> Sprite {
+    let pixels = image[location.1..location.1+dim.1].iter()
+        .map(|row| row[location.0..location.0+dim.0].to_vec())
+        .collect::<Vec<Vec<[u8;4]>>>();
+    Sprite::add(dim.0, dim.1, pixels)
+}
+
+///gets the display name of a clear. like "T-SPIN DOUBLE"
+fn clear_name(clear: &Clear) -> String {
+    let lines = match clear.lines {
+        0 => "",
+        1 => "SINGLE",
+        2 => "DOUBLE",
+        3 => "TRIPLE",
+        _ => "TETRIS",
+    };
+    match clear.spin {
+        Spin::None  => lines.to_string(),
+        Spin::Mini  => format!("MINI T-SPIN {}", lines).trim_end().to_string(),
+        Spin::Full  => format!("T-SPIN {}", lines).trim_end().to_string(),
+    }
+}
+
+///draws a board and everything on it
+pub struct Renderer {
+    left_panel: Sprite,
+    right_panel: Sprite,
+    cell: Sprite,
+    sprites: SpriteIndex,
+    preview_sprites: SpriteIndex,
+    padding: usize,
+    ///where the right panel starts in pixels
+    right: isize,
+    ///width and visible height of the board in blocks
+    board_dim: (usize, usize),
+    ///hidden rows above the board that arent drawn
+    hidden: usize,
+    pub screen_dim: (usize, usize),
+}
+
+impl Renderer {
+    ///attempts to load the backdrop and generate the piece sprites. the screen is sized to fit the boards settings
+    pub fn new(settings: &Settings) -> DynResult<Self> {
+        let backdrop = image::load_from_memory(BOARD_SPRITE)?.to_rgba();
+        let backdrop_dim = backdrop.dimensions();
+        let backdrop = backdrop.chunks_exact(backdrop_dim.0 as usize*4).map(|r|
+            r.chunks_exact(4).map(|p|
+                p.try_into().unwrap()
+            ).collect::<Vec<[u8;4]>>()
+        ).collect::<Vec<Vec<[u8;4]>>>();
+
+        let backdrop_dim = (backdrop_dim.0 as usize, backdrop_dim.1 as usize);
+        let board_width = settings.width*BLOCK_SIZE;
+
+        Ok(Self {
+            left_panel: crop(&backdrop, (0,0), (PANEL_WIDTH, backdrop_dim.1)),
+            right_panel: crop(&backdrop, (backdrop_dim.0-PANEL_WIDTH, 0), (PANEL_WIDTH, backdrop_dim.1)),
+            cell: crop(&backdrop, CELL_LOCATION, (BLOCK_SIZE, BLOCK_SIZE)),
+            sprites: gen_sprite_index(BLOCK_SIZE),
+            preview_sprites: gen_sprite_index(PREVIEW_BLOCK_SIZE),
+            padding: PANEL_WIDTH,
+            right: (PANEL_WIDTH+board_width) as isize,
+            board_dim: (settings.width, settings.height),
+            hidden: settings.buffer,
+            screen_dim: (PANEL_WIDTH*2+board_width, backdrop_dim.1.max(settings.height*BLOCK_SIZE)),
+        })
+    }
+
+    ///draws screen during game play. best is the personal best for the boards mode
+    pub fn draw(&self, screen: &mut drawing::Screen, board: &Board, best: Option<usize>) {
+        self.draw_backdrop(screen, board, best);
+        self.draw_matrix(screen, board);
+        if board.gameover {
+            //offsets were laid out for the default 10 wide board so shift them to stay centered
+            let shift = self.center_shift();
+            let mode = board.settings().mode;
+            let title = if board.completed {"FINISHED!"} else {"GAME OVER"};
+            screen.draw_text((195+shift ,40), title, 64.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
+            let message = {
+                if mode.is_timed() && board.completed {format!("TIME: {}",mode::format_frames(board.frames()))}
+                else {format!("SCORE: {}",board.score)}
+            };
+            screen.draw_text((225+shift,115), &message, 32.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
+            screen.draw_text((215+shift,200), "SPACE TO RESTART", 32.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
+            screen.draw_text((225+shift,600), &format!("SEED: {}",board.seed()), 16.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
+        }
+    }
+
+    ///draws the screen with the matrix, pieces and queue hidden so pausing cant be used to plan ahead
+    pub fn draw_paused(&self, screen: &mut drawing::Screen, board: &Board, best: Option<usize>) {
+        self.draw_backdrop(screen, board, best);
+        screen.draw_text((255+self.center_shift(), 40), "PAUSED", 64.0, &GAME_OVER_COLOR, drawing::DEBUG_FONT);
+    }
+
+    ///draws how many finesse faults the player has made this game
+    pub fn draw_faults(&self, screen: &mut drawing::Screen, faults: usize) {
+        screen.draw_text(FAULTS_LOCATION, &format!("FINESSE FAULTS: {}", faults), 16.0, &[255;4], drawing::DEBUG_FONT);
+    }
+
+    ///how far text laid out for the default 10 wide board has to move to stay centered over this one
+    fn center_shift(&self) -> isize {
+        (self.right-self.padding as isize)/2 - (settings::DEFAULT_WIDTH*BLOCK_SIZE) as isize/2
+    }
+
+    ///draws the panels, the empty grid and the stats
+    fn draw_backdrop(&self, screen: &mut drawing::Screen, board: &Board, best: Option<usize>) {
+        screen.wipe();
+        screen.draw_sprite(&self.left_panel, (0,0));
+        screen.draw_sprite(&self.right_panel, (self.right,0));
+        for row in 0..self.board_dim.1 {
+            for col in 0..self.board_dim.0 {
+                screen.draw_sprite(&self.cell, (((col*BLOCK_SIZE)+self.padding) as isize, (row*BLOCK_SIZE) as isize))
+            }
+        }
+        let mode = board.settings().mode;
+        let best = match best {
+            Some(frames) if mode.is_timed() => mode::format_frames(frames),
+            None if mode.is_timed()         => String::from("-"),
+            best                            => format!("{}",best.unwrap_or(0)),
+        };
+        screen.draw_text((9,191), &best, 32.0, &[255;4], drawing::DEBUG_FONT);
+        screen.draw_text((9,254), &format!("{}",board.score), 32.0, &[255;4], drawing::DEBUG_FONT);
+        screen.draw_text((83,287), &format!("{:02}",board.level), 32.0, &[255;4], drawing::DEBUG_FONT);
+        if let Some(clear) = &board.last_clear {
+            screen.draw_text(CLEAR_LOCATION, &clear_name(clear), 16.0, &[255;4], drawing::DEBUG_FONT);
+        }
+        let mut streaks = String::new();
+        if let Some(combo) = board.combo.filter(|c| *c > 0) {streaks.push_str(&format!("{} COMBO  ", combo))}
+        if board.back_to_back > 1 {streaks.push_str(&format!("B2B x{}", board.back_to_back-1))}
+        screen.draw_text(COMBO_LOCATION, &streaks, 16.0, &[255;4], drawing::DEBUG_FONT);
+        self.draw_mode(screen, board);
+    }
+
+    ///draws the set blocks, the shadow, the current, next and held pieces and the preview queue
+    fn draw_matrix(&self, screen: &mut drawing::Screen, board: &Board) {
+        //draw set blocks. the hidden rows are skipped
+        for (i, block) in board.data.iter().enumerate().skip(self.hidden*self.board_dim.0) {
+            if let Some(type_) = block {
+                let row = i/self.board_dim.0-self.hidden;
+                let col = i%self.board_dim.0;
+                let sprite = &self.sprites[type_];
+                screen.draw_sprite(sprite, (((col*sprite.width)+self.padding) as isize, (row*sprite.height) as isize))
+            }
+        }
+        let hidden = self.hidden as isize;
+
+        for (i, block) in board.piece.data.iter().enumerate() {
+            let row = i/board.piece.dim;
+            if *block && board.shadow.1+row as isize >= hidden {
+                let sprite = &self.sprites[&pieces::PieceType::Shadow];
+                let col = i%board.piece.dim;
+                screen.draw_sprite(
+                    sprite,
+                    (
+                        board.shadow.0*sprite.width as isize + ((col*sprite.width)+self.padding) as isize,
+                        (board.shadow.1-hidden)*sprite.height as isize + (row*sprite.height) as isize
+                    )
+                )
+            }
+        }
+
+        //draws a piece with its location in blocks offset by padding in pixels. skips blocks above the top of the screen
+        let mut draw_piece = |piece: &pieces::Piece, location: (isize, isize), padding: isize, sprite: &Sprite| {
+            for (i, block) in piece.data.iter().enumerate() {
+                let row = i/piece.dim;
+                if *block && location.1+row as isize >= 0 {
+                    let col = i%piece.dim;
+                    screen.draw_sprite(
+                        sprite,
+                        (
+                            location.0*sprite.width as isize + (col*sprite.width) as isize + padding,
+                            location.1*sprite.height as isize + (row*sprite.height) as isize
+                        )
+                    )
+                }
+            }
+        };
+        let next_piece = pieces::Piece::gen_piece(board.next_pieces[0], NEXT_PIECE_LOCATION, &board.piece_index);
+        draw_piece(&next_piece, NEXT_PIECE_LOCATION, self.right, &self.sprites[&next_piece.type_]);
+        if let Some(held) = &board.held_piece {
+            draw_piece(held, HELD_PIECE_LOCATION, 0, &self.sprites[&held.type_]);
+        }
+        let location = (board.piece.location.0, board.piece.location.1-hidden);
+        draw_piece(&board.piece, location, self.padding as isize, &self.sprites[&board.piece.type_]);
+        self.draw_previews(screen, board);
+    }
+
+    ///draws the mode name and how close the game is to ending
+    fn draw_mode(&self, screen: &mut drawing::Screen, board: &Board) {
+        let mode = board.settings().mode;
+        let goal = match (mode.line_goal(board.settings().lines_per_level), mode.frame_limit()) {
+            _ if mode == Mode::Dig              => format!("DUG {}/{}  {}", board.garbage_cleared(), board.settings().dig_lines, mode::format_frames(board.frames())),
+            (Some(lines), _) if mode.is_timed() => format!("{}/{}  {}", board.lines().min(lines), lines, mode::format_frames(board.frames())),
+            (Some(lines), _)                    => format!("LINES {}/{}", board.lines().min(lines), lines),
+            (None, Some(frames))                => format!("TIME LEFT {}", mode::format_frames(frames.saturating_sub(board.frames()))),
+            (None, None)                        => format!("LINES {}", board.lines()),
+        };
+        let location = |offset: (isize, isize)| (self.right+offset.0, offset.1);
+        screen.draw_text(location(MODE_LOCATION), &mode.to_string().to_uppercase(), 16.0, &[255;4], drawing::DEBUG_FONT);
+        screen.draw_text(location(GOAL_LOCATION), &goal, 16.0, &[255;4], drawing::DEBUG_FONT);
+    }
+
+    ///draws the rest of the preview queue shrunk down under the next piece
+    fn draw_previews(&self, screen: &mut drawing::Screen, board: &Board) {
+        for (i, type_) in board.next_pieces.iter().skip(1).enumerate() {
+            let (data, dim) = &board.piece_index[type_];
+            let sprite = &self.preview_sprites[type_];
+            let size = sprite.width as isize;
+            let x = self.right + PREVIEW_LOCATION.0 + (PREVIEW_WIDTH-*dim as isize*size)/2;
+            let y = PREVIEW_LOCATION.1 + i as isize*PREVIEW_SPACING;
+            for (i, block) in data.iter().enumerate() {
+                if *block {
+                    let row = (i/dim) as isize;
+                    let col = (i%dim) as isize;
+                    screen.draw_sprite(sprite, (x + col*size, y + row*size))
+                }
+            }
+        }
+    }
+}
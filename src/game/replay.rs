@@ -0,0 +1,183 @@
+use super::*;
+
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+///a recorded game. stores the seed, settings, and every input with the frame it happened on.
+///saved as text with the seed on the first line, the settings on the second, and one "frame input" per line after
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub settings: Settings,
+    pub inputs: Vec<(usize, Input)>,
+}
+
+impl Replay {
+    ///starts an empty replay of a fresh board
+    pub fn new(board: &Board) -> Self {
+        Self {
+            seed: board.seed(),
+            settings: board.settings().clone(),
+            inputs: Vec::new(),
+        }
+    }
+
+    ///records an input on the boards current frame. inputs after the game ends dont do anything so theyre skipped
+    pub fn record(&mut self, board: &Board, input: Input) {
+        if !board.gameover {
+            self.inputs.push((board.frames(), input));
+        }
+    }
+
+    ///forgets every input from frame on. used when the board gets rewound to that frame
+    pub fn rewind(&mut self, frame: usize) {
+        self.inputs.retain(|(stamp, _)| *stamp < frame);
+    }
+
+    ///attempts to build the board the replay starts on
+    pub fn board(&self) -> DynResult<Board> {
+        Board::with_settings(self.settings.clone(), self.seed)
+    }
+
+    ///attempts to save the replay to path
+    pub fn save(&self, path: &str) -> DynResult<()> {
+        let mut contents = format!("{}\n{}\n", self.seed, self.settings);
+        for (frame, input) in &self.inputs {
+            contents.push_str(&format!("{} {}\n", frame, input));
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    ///attempts to load a replay from path
+    pub fn load(path: &str) -> DynResult<Self> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+        let seed = lines.next().ok_or("Replay is missing its seed")?.trim().parse()?;
+        let settings = lines.next().ok_or("Replay is missing its settings")?.parse::<Settings>()?;
+        let inputs = lines.filter(|l| !l.trim().is_empty()).map(|line| {
+            let mut fields = line.split_whitespace();
+            let frame = fields.next().ok_or("Replay input is missing its frame")?.parse()?;
+            let input = fields.next().ok_or("Replay input is missing its input")?.parse::<Input>()?;
+            Ok((frame, input))
+        }).collect::<DynResult<Vec<(usize, Input)>>>()?;
+        Ok(Self {seed, settings, inputs})
+    }
+}
+
+
+
+///plays a replay back on its own board one frame at a time
+pub struct Playback {
+    replay: Replay,
+    pub board: Board,
+    next: usize,
+}
+
+impl Playback {
+    ///attempts to build the replays board
+    pub fn new(replay: Replay) -> DynResult<Self> {
+        Ok(Self {
+            board: replay.board()?,
+            replay,
+            next: 0,
+        })
+    }
+
+    ///applies every input recorded on the boards current frame. returns false once the replay has run out
+    pub fn step(&mut self) -> DynResult<bool> {
+        let frame = self.board.frames();
+        while let Some((stamp, input)) = self.replay.inputs.get(self.next) {
+            if *stamp > frame {break}
+            self.board.apply(*input)?;
+            self.next += 1;
+        }
+        Ok(self.next < self.replay.inputs.len())
+    }
+
+    ///starts the replay over
+    pub fn restart(&mut self) -> DynResult<()> {
+        self.board = self.replay.board()?;
+        self.next = 0;
+        Ok(())
+    }
+}
+
+
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::Move(Move::Down)                  => "down",
+            Self::Move(Move::Left)                  => "left",
+            Self::Move(Move::Right)                 => "right",
+            Self::Rotate(Rotate::Clockwise)         => "cw",
+            Self::Rotate(Rotate::CounterClockwise)  => "ccw",
+            Self::Rotate(Rotate::Half)              => "180",
+            Self::Hold                              => "hold",
+            Self::Drop                              => "drop",
+            Self::Update                            => "update",
+            Self::Tick                              => "tick",
+        })
+    }
+}
+
+impl FromStr for Input {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "down"      => Ok(Self::Move(Move::Down)),
+            "left"      => Ok(Self::Move(Move::Left)),
+            "right"     => Ok(Self::Move(Move::Right)),
+            "cw"        => Ok(Self::Rotate(Rotate::Clockwise)),
+            "ccw"       => Ok(Self::Rotate(Rotate::CounterClockwise)),
+            "180"       => Ok(Self::Rotate(Rotate::Half)),
+            "hold"      => Ok(Self::Hold),
+            "drop"      => Ok(Self::Drop),
+            "update"    => Ok(Self::Update),
+            "tick"      => Ok(Self::Tick),
+            _           => Err(format!("Unknown input \"{}\"", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn replays_play_back_the_same_game() {
+        let settings = Settings {randomizer: RandomizerType::History, scoring: ScoringRule::Guideline, buffer: 2, ..Settings::default()};
+        let mut board = Board::with_settings(settings, 11).unwrap();
+        let mut replay = Replay::new(&board);
+        //mash random inputs with the gravity running until it tops out
+        let inputs = [
+            Input::Move(Move::Left), Input::Move(Move::Right), Input::Move(Move::Down),
+            Input::Rotate(Rotate::Clockwise), Input::Rotate(Rotate::CounterClockwise), Input::Rotate(Rotate::Half),
+            Input::Hold, Input::Drop, Input::Tick,
+        ];
+        let mut rng = StdRng::seed_from_u64(3);
+        while !board.gameover && board.frames() < 5000 {
+            for input in [*inputs.choose(&mut rng).unwrap(), Input::Update].iter() {
+                replay.record(&board, *input);
+                board.apply(*input).unwrap();
+            }
+        }
+        assert!(board.placed() > 10);
+
+        let path = std::env::temp_dir().join(format!("tetris_replay_test_{}.log", std::process::id()));
+        let path = path.to_str().unwrap();
+        replay.save(path).unwrap();
+        let loaded = Replay::load(path);
+        fs::remove_file(path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded, replay);
+
+        let mut playback = Playback::new(loaded).unwrap();
+        while playback.step().unwrap() {}
+        assert_eq!(playback.board.get_board(), board.get_board());
+        assert_eq!((playback.board.score, playback.board.frames()), (board.score, board.frames()));
+    }
+}
@@ -0,0 +1,116 @@
+use super::{Spin, COMBO_POINTS, SOFT_DROP_POINTS, HARD_DROP_POINTS};
+
+use std::fmt;
+use std::str::FromStr;
+
+///how clears and drops are scored. the board and the AI both score through this so they cant disagree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoringRule {
+    ///the original house rule. the line score is multiplied by how far down each cleared row was, plus guideline spins and bonuses
+    RowHeight,
+    ///40/100/300/1200 times the level. no spins, combos or back to back
+    Nes,
+    ///the tetris guideline table times the level, with back to back and combos
+    Guideline,
+}
+
+impl ScoringRule {
+    ///every scoring rule. used for menus and parsing
+    pub const ALL: [Self; 3] = [Self::RowHeight, Self::Nes, Self::Guideline];
+
+    ///points for locking a piece. rows are the heights of the cleared rows counted up from the bottom of a board height rows tall.
+    ///combo is the combo count including this clear and back_to_back is the streak of difficult clears before it
+    pub fn clear_points(&self, rows: &[usize], height: usize, spin: Spin, level: usize, combo: usize, back_to_back: usize) -> usize {
+        let lines = rows.len();
+        let points = match self {
+            Self::RowHeight => {
+                let modifier = match lines {
+                    1 => 40,
+                    2 => 100,
+                    3 => 300,
+                    4 => 1200,
+                    _ => 3600
+                };
+                let line_points = rows.iter().map(|row|modifier*(height+1).saturating_sub(*row)).sum::<usize>();
+                line_points + spin_points(spin, lines)*(level+1)
+            },
+            Self::Nes => return match lines {
+                0 => 0,
+                1 => 40,
+                2 => 100,
+                3 => 300,
+                _ => 1200,
+            }*(level+1),
+            Self::Guideline => {
+                let points = match (spin, lines) {
+                    (Spin::None, 0) => 0,
+                    (Spin::None, 1) => 100,
+                    (Spin::None, 2) => 300,
+                    (Spin::None, 3) => 500,
+                    (Spin::None, _) => 800,
+                    _ => spin_points(spin, lines),
+                };
+                points*(level+1)
+            },
+        };
+        match lines {
+            0 => points,
+            _ => {
+                let bonus = if is_difficult(lines, spin) && back_to_back > 0 {points/2} else {0};
+                points + bonus + COMBO_POINTS*combo*(level+1)
+            },
+        }
+    }
+
+    ///points for each cell soft dropped
+    pub fn soft_drop_points(&self) -> usize {
+        SOFT_DROP_POINTS
+    }
+
+    ///points for each cell hard dropped. the NES didnt have hard drop so it gets nothing
+    pub fn hard_drop_points(&self) -> usize {
+        match self {
+            Self::Nes   => 0,
+            _           => HARD_DROP_POINTS,
+        }
+    }
+}
+
+///tetrises and spins keep the back to back going. everything else breaks it
+pub fn is_difficult(lines: usize, spin: Spin) -> bool {
+    lines >= 4 || spin != Spin::None
+}
+
+///the guideline T-spin table before its multiplied by level
+fn spin_points(spin: Spin, lines: usize) -> usize {
+    match (spin, lines) {
+        (Spin::None, _) => 0,
+        (Spin::Mini, 0) => 100,
+        (Spin::Mini, 1) => 200,
+        (Spin::Mini, _) => 400,
+        (Spin::Full, 0) => 400,
+        (Spin::Full, 1) => 800,
+        (Spin::Full, 2) => 1200,
+        (Spin::Full, _) => 1600,
+    }
+}
+
+impl fmt::Display for ScoringRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::RowHeight => "row_height",
+            Self::Nes       => "nes",
+            Self::Guideline => "guideline",
+        })
+    }
+}
+
+impl FromStr for ScoringRule {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter()
+            .find(|r| r.to_string() == s.to_lowercase())
+            .copied()
+            .ok_or(format!("Unknown scoring rule \"{}\"", s))
+    }
+}
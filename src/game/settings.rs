@@ -0,0 +1,114 @@
+use super::randomizer::RandomizerType;
+use super::mode::{self, Mode};
+use super::gravity::{self, Gravity};
+use super::scoring::ScoringRule;
+
+use std::fmt;
+use std::str::FromStr;
+
+///the most pieces the next queue can show
+pub const MAX_PREVIEWS: usize = 6;
+///default width of board in blocks
+pub const DEFAULT_WIDTH: usize = 10;
+///default visible height of board in blocks
+pub const DEFAULT_HEIGHT: usize = 20;
+///the smallest width or height a board can have. any smaller and the I piece wont fit
+pub const MIN_SIZE: usize = 4;
+
+///everything a board can be configured with when its built
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub mode: Mode,
+    ///how many garbage lines have to be cleared in dig mode
+    pub dig_lines: usize,
+    ///width of board in blocks
+    pub width: usize,
+    ///visible height of board in blocks
+    pub height: usize,
+    ///hidden rows above the visible board. pieces spawn in here if theres room, but locking in it tops out
+    pub buffer: usize,
+    pub randomizer: RandomizerType,
+    ///how many upcoming pieces are shown. between 1 and MAX_PREVIEWS
+    pub previews: usize,
+    ///how many frames a piece can sit on the stack before it locks. 0 locks as soon as gravity cant move it
+    pub lock_delay: usize,
+    ///how many times moving or rotating a grounded piece restarts the lock delay. None for infinite
+    pub move_resets: Option<usize>,
+    ///how fast pieces fall at each level
+    pub gravity: Gravity,
+    ///how many lines have to be cleared to go up a level
+    pub lines_per_level: usize,
+    ///how clears and drops are scored
+    pub scoring: ScoringRule,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Endless,
+            dig_lines: mode::DIG_LINES,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            buffer: 0,
+            randomizer: RandomizerType::Nes,
+            previews: 5,
+            lock_delay: 30,
+            move_resets: Some(15),
+            gravity: Gravity::Nes,
+            lines_per_level: gravity::LINES_PER_LEVEL,
+            scoring: ScoringRule::RowHeight,
+        }
+    }
+}
+
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,
+            "mode={}|dig_lines={}|width={}|height={}|buffer={}|randomizer={}|previews={}|lock_delay={}|move_resets={}|gravity={}|lines_per_level={}|scoring={}",
+            self.mode,
+            self.dig_lines,
+            self.width,
+            self.height,
+            self.buffer,
+            self.randomizer,
+            self.previews,
+            self.lock_delay,
+            self.move_resets.map_or(String::from("none"), |r| r.to_string()),
+            self.gravity,
+            self.lines_per_level,
+            self.scoring,
+        )
+    }
+}
+
+///parses "key=value" pairs split by '|'. missing keys keep their defaults
+impl FromStr for Settings {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut settings = Self::default();
+        for field in s.trim().split('|').filter(|f| !f.is_empty()) {
+            let mut pair = field.splitn(2, '=');
+            let (key, value) = (pair.next().unwrap_or(""), pair.next().unwrap_or(""));
+            let bad_value = |e: &dyn fmt::Display| format!("Bad value \"{}\" for {}: {}", value, key, e);
+            match key {
+                "mode"          => settings.mode = value.parse()?,
+                "dig_lines"     => settings.dig_lines = value.parse().map_err(|e| bad_value(&e))?,
+                "width"         => settings.width = value.parse().map_err(|e| bad_value(&e))?,
+                "height"        => settings.height = value.parse().map_err(|e| bad_value(&e))?,
+                "buffer"        => settings.buffer = value.parse().map_err(|e| bad_value(&e))?,
+                "randomizer"    => settings.randomizer = value.parse()?,
+                "previews"      => settings.previews = value.parse().map_err(|e| bad_value(&e))?,
+                "lock_delay"    => settings.lock_delay = value.parse().map_err(|e| bad_value(&e))?,
+                "move_resets"   => settings.move_resets = {
+                    if value == "none" {None}
+                    else {Some(value.parse().map_err(|e| bad_value(&e))?)}
+                },
+                "gravity"       => settings.gravity = value.parse()?,
+                "lines_per_level" => settings.lines_per_level = value.parse().map_err(|e| bad_value(&e))?,
+                "scoring"       => settings.scoring = value.parse()?,
+                _ => return Err(format!("Unknown setting \"{}\"", key)),
+            }
+        }
+        Ok(settings)
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+///the most placements a rewind buffer keeps by default
+pub const REWIND_PLACEMENTS: usize = 30;
+
+///everything that changes while a board is played. restoring one puts the board back exactly,
+///rngs included, without copying the piece index or subscribers
+#[derive(Clone)]
+pub struct Snapshot {
+    pub(super) settings: Settings,
+    pub(super) seed: u64,
+    pub(super) piece: pieces::Piece,
+    pub(super) shadow: (isize, isize),
+    pub(super) next_pieces: VecDeque<pieces::PieceType>,
+    pub(super) held_piece: Option<pieces::Piece>,
+    pub(super) data: BoardData,
+    pub(super) score: usize,
+    pub(super) cleared: usize,
+    pub(super) frame: usize,
+    pub(super) level: usize,
+    pub(super) gameover: bool,
+    pub(super) completed: bool,
+    pub(super) placed: usize,
+    pub(super) garbage_added: usize,
+    pub(super) garbage_cleared: usize,
+    pub(super) last_clear: Option<Clear>,
+    pub(super) tspins: usize,
+    pub(super) combo: Option<usize>,
+    pub(super) back_to_back: usize,
+    pub(super) last_kick: Option<(Rotate, usize)>,
+    pub(super) lock_frames: usize,
+    pub(super) lock_resets: usize,
+    pub(super) lowest_row: isize,
+    pub(super) rng: StdRng,
+    pub(super) garbage_rng: StdRng,
+    pub(super) randomizer: Box<dyn randomizer::Randomizer>,
+}
+
+impl Snapshot {
+    ///the frame the snapshot was taken on
+    pub fn frames(&self) -> usize {
+        self.frame
+    }
+
+    ///how many pieces had been placed when the snapshot was taken
+    pub fn placed(&self) -> usize {
+        self.placed
+    }
+}
+
+
+
+///keeps snapshots from the start of the last few placements so they can be undone
+pub struct Rewind {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+    ///snapshot from the start of the current piece. pushed once the piece is placed
+    current: Option<Snapshot>,
+}
+
+impl Rewind {
+    ///creates a rewind buffer that remembers up to capacity placements
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+            current: None,
+        }
+    }
+
+    ///call every frame. saves a snapshot each time a new piece starts and forgets everything if the board was reset
+    pub fn track(&mut self, board: &Board) {
+        match &self.current {
+            Some(current) if current.seed != board.seed() || current.placed > board.placed() => self.clear(),
+            Some(current) if current.placed == board.placed() => return,
+            _ => {},
+        }
+        if let Some(current) = self.current.take() {
+            if self.snapshots.len() == self.capacity {self.snapshots.pop_front();}
+            self.snapshots.push_back(current);
+        }
+        self.current = Some(board.snapshot());
+    }
+
+    ///puts the board back to the start of the last placed piece. returns the snapshot it went back to
+    pub fn undo(&mut self, board: &mut Board) -> DynResult<Option<&Snapshot>> {
+        match self.snapshots.pop_back() {
+            Some(snapshot) => {
+                board.restore(&snapshot)?;
+                self.current = Some(snapshot);
+                Ok(self.current.as_ref())
+            },
+            None => Ok(None),
+        }
+    }
+
+    ///how many placements can be undone
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    ///true if theres nothing to undo
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    ///forgets every snapshot
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restoring_puts_the_board_back_exactly() {
+        let mut board = Board::with_seed(4).unwrap();
+        let start = board.snapshot();
+        let mut played = vec!(board.get_board());
+        for _ in 0..10 {
+            board.drop_piece().unwrap();
+            played.push(board.get_board());
+        }
+        //the rngs come back too so the same pieces come out again
+        board.restore(&start).unwrap();
+        let mut replayed = vec!(board.get_board());
+        for _ in 0..10 {
+            board.drop_piece().unwrap();
+            replayed.push(board.get_board());
+        }
+        assert_eq!(replayed, played);
+    }
+
+    #[test]
+    fn undo_goes_back_past_a_hold() {
+        let mut board = Board::with_seed(4).unwrap();
+        let mut rewind = Rewind::new(REWIND_PLACEMENTS);
+        rewind.track(&board);
+        board.drop_piece().unwrap();
+        rewind.track(&board);
+        let before_hold = board.get_board();
+        assert!(board.hold_piece().unwrap());
+        rewind.track(&board);
+        board.drop_piece().unwrap();
+        rewind.track(&board);
+        assert_eq!(rewind.len(), 2);
+
+        assert_eq!(rewind.undo(&mut board).unwrap().map(Snapshot::placed), Some(1));
+        assert_eq!(board.get_board(), before_hold);
+        assert!(board.held_piece.is_none());
+        assert_eq!(rewind.undo(&mut board).unwrap().map(Snapshot::placed), Some(0));
+        assert!(rewind.undo(&mut board).unwrap().is_none());
+    }
+
+    #[test]
+    fn rewind_keeps_up_to_its_capacity() {
+        let mut board = Board::with_seed(4).unwrap();
+        let mut rewind = Rewind::new(3);
+        for _ in 0..10 {
+            rewind.track(&board);
+            board.drop_piece().unwrap();
+        }
+        rewind.track(&board);
+        assert_eq!(rewind.len(), 3);
+        //only the last 3 placements come back
+        let undone = (0..4).map(|_| rewind.undo(&mut board).unwrap().map(Snapshot::placed)).collect::<Vec<Option<usize>>>();
+        assert_eq!(undone, vec!(Some(9), Some(8), Some(7), None));
+        assert!(rewind.is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+//! Super Rotation System kick tables.
+//! offsets are written like the guideline tables (x right, y UP) so they can be checked against them.
+//! flip the y before applying them to the board
+
+use super::pieces::{PieceType, Rotation};
+
+///kicks for J, L, S, T and Z. indexed by [from][to] for quarter turns
+const JLSTZ_KICKS: [[&[(isize, isize)]; 4]; 4] = [
+    //from north
+    [&[], &[(0,0), (-1,0), (-1, 1), (0,-2), (-1,-2)], &[], &[(0,0), ( 1,0), ( 1, 1), (0,-2), ( 1,-2)]],
+    //from east
+    [&[(0,0), ( 1,0), ( 1,-1), (0, 2), ( 1, 2)], &[], &[(0,0), ( 1,0), ( 1,-1), (0, 2), ( 1, 2)], &[]],
+    //from south
+    [&[], &[(0,0), (-1,0), (-1, 1), (0,-2), (-1,-2)], &[], &[(0,0), ( 1,0), ( 1, 1), (0,-2), ( 1,-2)]],
+    //from west
+    [&[(0,0), (-1,0), (-1,-1), (0, 2), (-1, 2)], &[], &[(0,0), (-1,0), (-1,-1), (0, 2), (-1, 2)], &[]],
+];
+
+///kicks for I. indexed by [from][to] for quarter turns
+const I_KICKS: [[&[(isize, isize)]; 4]; 4] = [
+    //from north
+    [&[], &[(0,0), (-2,0), ( 1,0), (-2,-1), ( 1, 2)], &[], &[(0,0), (-1,0), ( 2,0), (-1, 2), ( 2,-1)]],
+    //from east
+    [&[(0,0), ( 2,0), (-1,0), ( 2, 1), (-1,-2)], &[], &[(0,0), (-1,0), ( 2,0), (-1, 2), ( 2,-1)], &[]],
+    //from south
+    [&[], &[(0,0), ( 1,0), (-2,0), ( 1,-2), (-2, 1)], &[], &[(0,0), ( 2,0), (-1,0), ( 2, 1), (-1,-2)]],
+    //from west
+    [&[(0,0), ( 1,0), (-2,0), ( 1,-2), (-2, 1)], &[], &[(0,0), (-2,0), ( 1,0), (-2,-1), ( 1, 2)], &[]],
+];
+
+///kicks for half turns. SRS doesnt define any so these are the common SRS+ ones. indexed by from
+const HALF_KICKS: [&[(isize, isize)]; 4] = [
+    &[(0,0), ( 0, 1), ( 1, 1), (-1, 1), ( 1,0), (-1,0)],
+    &[(0,0), ( 1, 0), ( 1, 2), ( 1, 1), ( 0,2), ( 0,1)],
+    &[(0,0), ( 0,-1), (-1,-1), ( 1,-1), (-1,0), ( 1,0)],
+    &[(0,0), (-1, 0), (-1, 2), (-1, 1), ( 0,2), ( 0,1)],
+];
+
+///the O piece never kicks
+const NO_KICKS: &[(isize, isize)] = &[(0,0)];
+
+///gets the offsets to try in order when rotating a piece from one state to another
+pub fn get_kicks(type_: PieceType, from: Rotation, to: Rotation) -> &'static [(isize, isize)] {
+    let (from, to) = (from.index(), to.index());
+    match type_ {
+        PieceType::O                => NO_KICKS,
+        _ if (from+2)%4 == to       => HALF_KICKS[from],
+        PieceType::I                => I_KICKS[from][to],
+        _                           => JLSTZ_KICKS[from][to],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_guideline() {
+        assert_eq!(get_kicks(PieceType::T, Rotation::North, Rotation::East), &[(0,0), (-1,0), (-1, 1), (0,-2), (-1,-2)]);
+        assert_eq!(get_kicks(PieceType::I, Rotation::North, Rotation::East), &[(0,0), (-2,0), ( 1,0), (-2,-1), ( 1, 2)]);
+        assert_eq!(get_kicks(PieceType::O, Rotation::North, Rotation::East), &[(0,0)]);
+    }
+
+    #[test]
+    fn quarter_turns_undo_each_other() {
+        for type_ in [PieceType::I, PieceType::J, PieceType::L, PieceType::S, PieceType::T, PieceType::Z].iter() {
+            for from in Rotation::ALL.iter() {
+                for to in [from.turned(1), from.turned(3)].iter() {
+                    let kicks = get_kicks(*type_, *from, *to);
+                    assert_eq!((kicks.len(), kicks[0]), (5, (0,0)));
+                    let back = get_kicks(*type_, *to, *from).iter().map(|(x, y)| (-x, -y)).collect::<Vec<_>>();
+                    assert_eq!(kicks, &back[..], "{:?} {:?} to {:?}", type_, from, to);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn half_turns_use_their_own_table() {
+        for type_ in [PieceType::I, PieceType::T, PieceType::S].iter() {
+            for from in Rotation::ALL.iter() {
+                let kicks = get_kicks(*type_, *from, from.turned(2));
+                assert_eq!((kicks.len(), kicks[0]), (6, (0,0)));
+                assert_eq!(kicks, HALF_KICKS[from.index()]);
+            }
+        }
+        assert_eq!(get_kicks(PieceType::O, Rotation::North, Rotation::South), &[(0,0)]);
+    }
+}
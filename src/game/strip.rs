@@ -3,13 +3,28 @@
 use super::*;
 
 ///the data returned to AI from get_board()
+#[derive(Clone, Debug, PartialEq)]
 pub struct StrippedBoard {
     pub piece: pieces::Piece,
-    pub next_piece: pieces::Piece,
+    ///the preview queue. the first piece is the one that spawns next
+    pub next_pieces: Vec<pieces::Piece>,
     pub held_piece: Option<pieces::Piece>,
     pub data:   Vec<bool>,
+    ///width of data in blocks
+    pub width: usize,
+    ///height of data in blocks including the hidden buffer
+    pub height: usize,
+    ///hidden rows at the top of data
+    pub hidden: usize,
     pub score: usize,
     pub level: usize,
+    ///how many lines have been cleared
+    pub lines: usize,
+    pub lines_per_level: usize,
+    pub combo: Option<usize>,
+    pub back_to_back: usize,
+    ///how the board scores clears. the AI predicts points with it
+    pub scoring: ScoringRule,
     pub gameover: bool,
 }
 
@@ -17,11 +32,19 @@ impl StrippedBoard {
     pub fn get(board: &Board) -> Self {
         Self {
             piece: board.piece.clone(),
-            next_piece: pieces::Piece::gen_piece(board.next_piece, board.spawn, &board.piece_index),
+            next_pieces: board.next_pieces.iter().map(|type_| pieces::Piece::gen_piece(*type_, board.spawn, &board.piece_index)).collect(),
             held_piece: board.held_piece.clone(),
             data: board.data.iter().map(|cell| cell.is_some()).collect(),
+            width: board.width,
+            height: board.height,
+            hidden: board.hidden,
             score: board.score,
             level: board.level,
+            lines: board.cleared,
+            lines_per_level: board.settings.lines_per_level,
+            combo: board.combo,
+            back_to_back: board.back_to_back,
+            scoring: board.settings.scoring,
             gameover: board.gameover,
         }
     }
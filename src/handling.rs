@@ -0,0 +1,180 @@
+//! delayed auto shift and auto repeat for held keys.
+//! doesnt know about the engine, the caller says which keys are down each frame
+
+use crate::game::Move;
+
+///setting das, arr or soft drop factor to this makes it instant
+pub const INSTANT: usize = 0;
+///how many cells an instant shift asks for. moves as far as the piece can go
+pub const ALL_CELLS: usize = usize::MAX;
+
+///how held keys repeat. all timings are in frames
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Handling {
+    ///delayed auto shift. how long left or right has to be held before it starts repeating
+    pub das: usize,
+    ///auto repeat rate. frames between each repeat once das is charged. INSTANT moves to the wall
+    pub arr: usize,
+    ///how many times faster than gravity soft drop is. INSTANT drops to the floor without locking
+    pub soft_drop_factor: usize,
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Self {
+            das: 10,
+            arr: 2,
+            soft_drop_factor: 20,
+        }
+    }
+}
+
+///the state of a key this frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Key {
+    ///went down this frame
+    pub pressed: bool,
+    ///is down. true on the frame its pressed too
+    pub held: bool,
+}
+
+///turns key states into moves every frame
+#[derive(Clone, Debug)]
+pub struct AutoRepeat {
+    handling: Handling,
+    ///the direction being shifted and how many frames its been held
+    shifting: Option<(Move, usize)>,
+    ///how many frames soft drop has been held
+    dropping: Option<usize>,
+}
+
+impl AutoRepeat {
+    pub fn new(handling: Handling) -> Self {
+        Self {
+            handling,
+            shifting: None,
+            dropping: None,
+        }
+    }
+
+    ///takes this frames keys and returns each direction to move and how many cells. ALL_CELLS means as far as possible.
+    ///gravity is how many frames the board takes to drop a cell on its own
+    pub fn update(&mut self, left: Key, right: Key, down: Key, gravity: usize) -> Vec<(Move, usize)> {
+        let mut moves = Vec::new();
+        if let Some(shift) = self.shift(left, right) {moves.push(shift)}
+        if let Some(drop) = self.soft_drop(down, gravity) {moves.push(drop)}
+        moves
+    }
+
+    ///forgets held keys. used when the game restarts so a held key doesnt carry charged das over
+    pub fn reset(&mut self) {
+        self.shifting = None;
+        self.dropping = None;
+    }
+
+    ///left and right. the newest press wins and letting go falls back to the other key if its still held
+    fn shift(&mut self, left: Key, right: Key) -> Option<(Move, usize)> {
+        let key = |direction: Move| if direction == Move::Left {left} else {right};
+        if left.pressed || right.pressed {
+            let direction = if right.pressed {Move::Right} else {Move::Left};
+            self.shifting = Some((direction, 0));
+            return self.repeat(direction, 0)
+        }
+        match self.shifting {
+            Some((direction, frames)) if key(direction).held => {
+                self.shifting = Some((direction, frames+1));
+                self.repeat(direction, frames+1)
+            },
+            Some((Move::Left, _)) if right.held => {self.shifting = Some((Move::Right, 0)); None},
+            Some((Move::Right, _)) if left.held => {self.shifting = Some((Move::Left, 0)); None},
+            _ => {self.shifting = None; None},
+        }
+    }
+
+    ///how far a shift held for frames moves this frame
+    fn repeat(&self, direction: Move, frames: usize) -> Option<(Move, usize)> {
+        let Handling {das, arr, ..} = self.handling;
+        match arr {
+            _ if frames == 0 && das != INSTANT  => Some((direction, 1)),
+            _ if frames < das                   => None,
+            INSTANT                             => Some((direction, ALL_CELLS)),
+            arr => match (frames-das)%arr {
+                0 => Some((direction, 1)),
+                _ => None,
+            },
+        }
+    }
+
+    ///soft drop repeats at gravity divided by the soft drop factor
+    fn soft_drop(&mut self, down: Key, gravity: usize) -> Option<(Move, usize)> {
+        let frames = match (down.pressed, self.dropping) {
+            (true, _)                   => 0,
+            (false, Some(frames)) if down.held => frames+1,
+            _                           => {self.dropping = None; return None},
+        };
+        self.dropping = Some(frames);
+        match self.handling.soft_drop_factor {
+            INSTANT => Some((Move::Down, ALL_CELLS)),
+            factor if frames.is_multiple_of((gravity/factor).max(1)) => Some((Move::Down, 1)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UP: Key = Key {pressed: false, held: false};
+    const PRESSED: Key = Key {pressed: true, held: true};
+    const HELD: Key = Key {pressed: false, held: true};
+
+    ///holds left for frames frames after pressing it and returns what each frame moved
+    fn hold_left(handling: Handling, frames: usize) -> Vec<Vec<(Move, usize)>> {
+        let mut repeat = AutoRepeat::new(handling);
+        let mut moves = vec!(repeat.update(PRESSED, UP, UP, 48));
+        for _ in 0..frames {moves.push(repeat.update(HELD, UP, UP, 48))}
+        moves
+    }
+
+    #[test]
+    fn das_charges_before_repeating() {
+        let moves = hold_left(Handling {das: 3, arr: 2, soft_drop_factor: 20}, 7);
+        let left = vec!((Move::Left, 1));
+        assert_eq!(moves, vec!(left.clone(), vec!(), vec!(), left.clone(), vec!(), left.clone(), vec!(), left));
+    }
+
+    #[test]
+    fn instant_arr_goes_to_the_wall() {
+        let moves = hold_left(Handling {das: 2, arr: INSTANT, soft_drop_factor: 20}, 3);
+        let all = vec!((Move::Left, ALL_CELLS));
+        assert_eq!(moves, vec!(vec!((Move::Left, 1)), vec!(), all.clone(), all));
+        //with no das either the first press goes all the way
+        let moves = hold_left(Handling {das: INSTANT, arr: INSTANT, soft_drop_factor: 20}, 0);
+        assert_eq!(moves, vec!(vec!((Move::Left, ALL_CELLS))));
+    }
+
+    #[test]
+    fn newest_direction_wins() {
+        let mut repeat = AutoRepeat::new(Handling::default());
+        assert_eq!(repeat.update(PRESSED, UP, UP, 48), vec!((Move::Left, 1)));
+        assert_eq!(repeat.update(HELD, PRESSED, UP, 48), vec!((Move::Right, 1)));
+        //letting go of right falls back to the held left key, which has to charge das again
+        assert_eq!(repeat.update(HELD, UP, UP, 48), vec!());
+        for _ in 0..Handling::default().das-1 {assert_eq!(repeat.update(HELD, UP, UP, 48), vec!())}
+        assert_eq!(repeat.update(HELD, UP, UP, 48), vec!((Move::Left, 1)));
+    }
+
+    #[test]
+    fn soft_drop_follows_gravity() {
+        let mut repeat = AutoRepeat::new(Handling {das: 10, arr: 2, soft_drop_factor: 4});
+        let mut moves = repeat.update(UP, UP, PRESSED, 8);
+        for _ in 0..4 {moves.extend(repeat.update(UP, UP, HELD, 8))}
+        assert_eq!(moves, vec!((Move::Down, 1), (Move::Down, 1), (Move::Down, 1)));
+        assert_eq!(repeat.update(UP, UP, UP, 8), vec!());
+
+        let mut repeat = AutoRepeat::new(Handling {das: 10, arr: 2, soft_drop_factor: INSTANT});
+        assert_eq!(repeat.update(UP, UP, PRESSED, 8), vec!((Move::Down, ALL_CELLS)));
+        assert_eq!(repeat.update(UP, UP, HELD, 8), vec!((Move::Down, ALL_CELLS)));
+    }
+}
@@ -0,0 +1,256 @@
+use crate::game::{Board, Mode};
+
+use dynerr::*;
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind::NotFound;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+///how many entries each mode keeps
+pub const LEADERBOARD_SIZE: usize = 10;
+///the version written at the top of the scores file. bump it if the entry format changes
+const VERSION: usize = 1;
+///the name of the scores file inside the data directory
+const SCORES_FILE: &str = "scores.txt";
+///where personal bests used to be kept. imported the first time the leaderboard is loaded
+const LEGACY_FILE: &str = "highscore.log";
+///the name legacy bests are imported with
+const LEGACY_NAME: &str = "OLD BEST";
+///seconds in a day
+const DAY: u64 = 86400;
+
+///gets the directory scores are kept in. $XDG_DATA_HOME or %APPDATA% if theyre set, ~/.local/share if not, and the current directory as a last resort
+fn data_dir() -> PathBuf {
+    let base = env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .unwrap_or_default();
+    base.join("tetris")
+}
+
+///formats unix seconds as a date like "2020-06-01". 0 is an unknown date
+pub fn format_date(seconds: u64) -> String {
+    if seconds == 0 {return String::from("-")}
+    //days to a civil date. from howard hinnants date algorithms
+    let days = (seconds/DAY) as i64 + 719468;
+    let era = days/146097;
+    let day_of_era = days - era*146097;
+    let year_of_era = (day_of_era - day_of_era/1460 + day_of_era/36524 - day_of_era/146096)/365;
+    let day_of_year = day_of_era - (365*year_of_era + year_of_era/4 - year_of_era/100);
+    let month_index = (5*day_of_year + 2)/153;
+    let day = day_of_year - (153*month_index + 2)/5 + 1;
+    let month = if month_index < 10 {month_index + 3} else {month_index - 9};
+    let year = year_of_era + era*400 + if month <= 2 {1} else {0};
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+///a finished game on the leaderboard
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    ///the players name, or a label for the AI that played
+    pub name: String,
+    pub score: usize,
+    pub level: usize,
+    pub lines: usize,
+    ///how long the game took in frames
+    pub frames: usize,
+    ///when the game was played in unix seconds. 0 if its not known
+    pub date: u64,
+}
+
+impl Entry {
+    ///records a board as it is now under name. names are kept to one line so they cant break the file
+    pub fn new(board: &Board, name: &str) -> Self {
+        Self {
+            name: name.replace(|c: char| c.is_control(), " "),
+            score: board.score,
+            level: board.level,
+            lines: board.lines(),
+            frames: board.frames(),
+            date: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        }
+    }
+
+    ///what the entry is ranked by. the time for timed modes and the score for everything else
+    pub fn result(&self, mode: Mode) -> usize {
+        if mode.is_timed() {self.frames}
+        else {self.score}
+    }
+}
+
+///the top games for each mode. stored in a versioned text file in the users data directory with one
+///"mode|score|level|lines|frames|date|name" per line
+#[derive(Clone, Debug, Default)]
+pub struct Leaderboard {
+    entries: HashMap<Mode, Vec<Entry>>,
+}
+
+impl Leaderboard {
+    ///attempts to load the leaderboard. if there isnt one yet the bests in "highscore.log" get imported
+    pub fn get() -> DynResult<Self> {
+        match fs::read_to_string(data_dir().join(SCORES_FILE)) {
+            Ok(contents) => Ok(contents.parse()?),
+            Err(e) if e.kind() == NotFound => Self::import_legacy(),
+            Err(e) => Err(e)?,
+        }
+    }
+
+    ///attempts to build a leaderboard from the old "mode best" per line highscore file
+    fn import_legacy() -> DynResult<Self> {
+        let mut leaderboard = Self::default();
+        let contents = match fs::read_to_string(LEGACY_FILE) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == NotFound => return Ok(leaderboard),
+            Err(e) => Err(e)?,
+        };
+        for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+            let mut fields = line.split_whitespace();
+            let (mode, best) = match (fields.next(), fields.next()) {
+                (Some(score), None) => (Mode::Endless, score.parse::<usize>()?),
+                (Some(mode), Some(best)) => (mode.parse::<Mode>()?, best.parse::<usize>()?),
+                _ => continue,
+            };
+            let mut entry = Entry {name: LEGACY_NAME.to_string(), score: 0, level: 0, lines: 0, frames: 0, date: 0};
+            if mode.is_timed() {entry.frames = best}
+            else {entry.score = best}
+            leaderboard.insert(mode, entry);
+        }
+        Ok(leaderboard)
+    }
+
+    ///attempts to write the leaderboard to the data directory
+    pub fn save(&self) -> DynResult<()> {
+        let dir = data_dir();
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(SCORES_FILE), self.to_string())?;
+        Ok(())
+    }
+
+    ///gets the entries for a mode from best to worst
+    pub fn entries(&self, mode: Mode) -> &[Entry] {
+        self.entries.get(&mode).map_or(&[], |entries| entries.as_slice())
+    }
+
+    ///gets the best result for a mode if its been played
+    pub fn best(&self, mode: Mode) -> Option<usize> {
+        self.entries(mode).first().map(|entry| entry.result(mode))
+    }
+
+    ///adds a finished game under name and saves if it made the table. returns its place counting from 0.
+    ///timed modes only count once the goal is finished
+    pub fn submit(&mut self, board: &Board, name: &str) -> DynResult<Option<usize>> {
+        let mode = board.settings().mode;
+        if mode.is_timed() && !board.completed {return Ok(None)}
+        let place = self.insert(mode, Entry::new(board, name));
+        if place.is_some() {self.save()?}
+        Ok(place)
+    }
+
+    ///puts an entry in its place. ties go under the older entry. returns the place if it made the table
+    fn insert(&mut self, mode: Mode, entry: Entry) -> Option<usize> {
+        let entries = self.entries.entry(mode).or_default();
+        let result = entry.result(mode);
+        let place = entries.iter().position(|e| mode.is_better(result, e.result(mode))).unwrap_or(entries.len());
+        if place >= LEADERBOARD_SIZE {return None}
+        entries.insert(place, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+        Some(place)
+    }
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version {}", VERSION)?;
+        for mode in Mode::ALL.iter() {
+            for e in self.entries(*mode) {
+                writeln!(f, "{}|{}|{}|{}|{}|{}|{}", mode, e.score, e.level, e.lines, e.frames, e.date, e.name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Leaderboard {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().filter(|l| !l.trim().is_empty());
+        match lines.next().map(|l| l.trim()) {
+            Some(header) if header == format!("version {}", VERSION) => {},
+            Some(header) => return Err(format!("Unsupported scores file \"{}\". Expected version {}", header, VERSION)),
+            None => return Ok(Self::default()),
+        }
+        let mut leaderboard = Self::default();
+        for line in lines {
+            let fields = line.splitn(7, '|').collect::<Vec<&str>>();
+            let number = |i: usize| fields[i].parse().map_err(|e| format!("Bad scores entry \"{}\": {}", line, e));
+            if fields.len() != 7 {return Err(format!("Bad scores entry \"{}\"", line))}
+            let entry = Entry {
+                score: number(1)?,
+                level: number(2)?,
+                lines: number(3)?,
+                frames: number(4)?,
+                date: fields[5].parse().map_err(|e| format!("Bad scores entry \"{}\": {}", line, e))?,
+                name: fields[6].to_string(),
+            };
+            leaderboard.insert(fields[0].parse()?, entry);
+        }
+        Ok(leaderboard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///an entry with just a name, score and time
+    fn entry(name: &str, score: usize, frames: usize) -> Entry {
+        Entry {name: name.to_string(), score, level: 0, lines: 0, frames, date: 0}
+    }
+
+    #[test]
+    fn parses_what_it_writes() {
+        let leaderboard = "version 1\nendless|1200|3|31|5400|1591000000|someone | with bars\nsprint|0|4|40|3000|0|AI\n".parse::<Leaderboard>().unwrap();
+        assert_eq!(leaderboard.entries(Mode::Endless), [Entry {name: "someone | with bars".to_string(), score: 1200, level: 3, lines: 31, frames: 5400, date: 1591000000}]);
+        assert_eq!(leaderboard.best(Mode::Sprint), Some(3000));
+        assert_eq!(leaderboard.best(Mode::Ultra), None);
+        let reparsed = leaderboard.to_string().parse::<Leaderboard>().unwrap();
+        for mode in Mode::ALL.iter() {
+            assert_eq!(reparsed.entries(*mode), leaderboard.entries(*mode));
+        }
+    }
+
+    #[test]
+    fn checks_the_version() {
+        assert!("".parse::<Leaderboard>().unwrap().entries(Mode::Endless).is_empty());
+        assert!("version 2\nendless|1|0|0|0|0|a".parse::<Leaderboard>().unwrap_err().contains("Expected version 1"));
+        assert!("endless|1|0|0|0|0|a".parse::<Leaderboard>().is_err());
+        assert!("version 1\nendless|1|0|0|0".parse::<Leaderboard>().is_err());
+        assert!("version 1\nendless|lots|0|0|0|0|a".parse::<Leaderboard>().is_err());
+    }
+
+    #[test]
+    fn keeps_the_top_entries_in_order() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=LEADERBOARD_SIZE {
+            assert_eq!(leaderboard.insert(Mode::Endless, entry("", score*100, 0)), Some(0));
+        }
+        //too low to make it, then a tie going under the entry already there
+        assert_eq!(leaderboard.insert(Mode::Endless, entry("low", 50, 0)), None);
+        assert_eq!(leaderboard.insert(Mode::Endless, entry("tie", 500, 0)), Some(LEADERBOARD_SIZE-4));
+        let entries = leaderboard.entries(Mode::Endless);
+        assert_eq!(entries.len(), LEADERBOARD_SIZE);
+        assert!(entries.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(entries[LEADERBOARD_SIZE-4].name, "tie");
+        assert_eq!(entries.last().unwrap().score, 200);
+
+        //timed modes rank the fastest first
+        assert_eq!(leaderboard.insert(Mode::Sprint, entry("slow", 0, 5000)), Some(0));
+        assert_eq!(leaderboard.insert(Mode::Sprint, entry("fast", 0, 3000)), Some(0));
+        assert_eq!(leaderboard.best(Mode::Sprint), Some(3000));
+    }
+}
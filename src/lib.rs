@@ -1,77 +1,199 @@
 #![feature(test)]
 pub mod game;
-pub use game::{Board, Move};
-mod ai;
+pub use game::{Board, Move, Rotate, Settings, Input, Replay, Playback, Event, Snapshot, Rewind};
+pub mod highscore;
+pub mod handling;
+pub mod ai;
+#[cfg(feature = "render")]
+mod controls;
+#[cfg(feature = "render")]
+mod menu;
+#[cfg(feature = "render")]
 mod train;
 
+#[cfg(feature = "render")]
 use dynerr::*;
+#[cfg(feature = "render")]
 use engine;
+#[cfg(feature = "render")]
+use controls::{Action, Controls};
+#[cfg(feature = "render")]
+use std::sync::mpsc;
 
 ///the target fps
+#[cfg(feature = "render")]
 const TARGET_FPS: u64 = 60;
+#[cfg(feature = "render")]
 const GAME_TITLE: &str = "Tetris";
+///the leaderboard name for games the built in AI played
+#[cfg(feature = "render")]
+const AI_NAME: &str = "AI";
+///the leaderboard name for games the trained AI from best.log played
+#[cfg(feature = "render")]
+const AI_BEST_NAME: &str = "AI BEST.LOG";
+///where the last game is saved for replaying
+#[cfg(feature = "render")]
+const REPLAY_FILE: &str = "replay.log";
 
-pub fn run(train: bool, auto_loop: bool, use_best: bool) {
+///what the window is showing
+#[cfg(feature = "render")]
+enum State {
+    Title(menu::Menu),
+    Playing,
+    ///the game is frozen and hidden behind the pause menu
+    Paused(menu::Menu),
+    ///the settings menu. true if it was opened from the pause menu
+    Settings(menu::Menu, bool),
+    Scores(menu::Scores),
+}
+
+///a game being played and everything keeping track of it
+#[cfg(feature = "render")]
+struct Session {
+    board: Board,
+    events: mpsc::Receiver<Event>,
+    replay: Replay,
+    replay_saved: bool,
+    rewind: Rewind,
+    ///games with undos dont count for personal bests
+    undone: bool,
+    auto_repeat: handling::AutoRepeat,
+    finesse: ai::FinesseTracker,
+    ///events for finesse. read after every input so presses go to the piece they were made on
+    finesse_events: mpsc::Receiver<Event>,
+}
+
+#[cfg(feature = "render")]
+impl Session {
+    ///attempts to start a new game on a random seed
+    fn new(settings: Settings, handling: handling::Handling) -> DynResult<Self> {
+        let mut board = Board::with_settings(settings, rand::random())?;
+        let mut finesse = ai::FinesseTracker::default();
+        finesse.spawned(board.get_board());
+        Ok(Self {
+            events: board.subscribe(),
+            finesse_events: board.subscribe(),
+            replay: Replay::new(&board),
+            board,
+            replay_saved: false,
+            rewind: Rewind::new(game::snapshot::REWIND_PLACEMENTS),
+            undone: false,
+            auto_repeat: handling::AutoRepeat::new(handling),
+            finesse,
+        })
+    }
+
+    ///applies an input to the board and records it to the replay
+    fn play(&mut self, input: Input) -> DynResult<bool> {
+        self.replay.record(&self.board, input);
+        let played = self.board.apply(input)?;
+        self.track_finesse();
+        Ok(played)
+    }
+
+    ///tells the finesse tracker about pieces that came out or locked since it last checked
+    fn track_finesse(&mut self) {
+        for event in self.finesse_events.try_iter() {
+            match event {
+                Event::PieceSpawned {board, ..} | Event::Hold {board, ..} => self.finesse.spawned(*board),
+                Event::PieceLocked {location, rotation, ..} => {self.finesse.locked(location, rotation);},
+                _ => {},
+            }
+        }
+    }
+
+    ///saves the replay unless its already been saved
+    fn save_replay(&mut self) -> DynResult<()> {
+        if !self.replay_saved {
+            self.replay.save(REPLAY_FILE)?;
+            self.replay_saved = true;
+        }
+        Ok(())
+    }
+
+    ///saves the replay and starts the board over with a new seed
+    fn restart(&mut self) -> DynResult<()> {
+        self.save_replay()?;
+        self.board.reset()?;
+        self.replay = Replay::new(&self.board);
+        self.replay_saved = false;
+        self.undone = false;
+        self.auto_repeat.reset();
+        self.finesse.reset();
+        self.track_finesse();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "render")]
+#[allow(clippy::too_many_arguments)]
+pub fn run(train: bool, auto_loop: bool, use_best: bool, settings: Settings, handling: handling::Handling, search: ai::Search, replay_file: Option<String>, name: String) {
     if train {
         check!(train::train());
         return
     }
+    let controls = check!(Controls::get());
+    if let Some(path) = replay_file {
+        play_replay(&path, controls);
+        return
+    }
 
     //UNTRAINED                        3   : 0.500   : 0.500   : 0.250   : 0.750   : 0.000   : 0.500   : 3.500   : 2   : 0.750
-    //         ???  |    ?? |    ??? | 2   : 0.919   : 0.006   : 0.572   : 0.049   : 0.143   : 0.012   : 0.995   : 0   : 0.392
-    //      392860  |    8  |    227 | 2   : 0.939   : 0.004   : 0.240   : 0.002   : 0.102   : 0.024   : 0.920   : 0   : 0.200
-    //      693944  |    6  |    196 | 4   : 0.79000 : 0.00100 : 0.14900 : 0.05000 : 0.09200 : 0.01900 : 0.63200 : 1   : 0.06600
-    //      1189945 |   18  |    485 | 4   : 0.79000 : 0.00100 : 0.33926 : 0.05000 : 0.09200 : 0.01900 : 0.42276 : 0   : 0.06300
-    //      1145311 |   21  |    556 | 4.0 : 0.91910 : 0.00000 : 0.56862 : 0.09960 : 0.32070 : 0.06357 : 1.00564 : 1.0 : 0.38466
-    //208 | 1170614 |   16  |    443 | 0.0 : -0.61509 : 0.00227 : 1.49888 : 0.89981 : -1.63555 : -0.83318 : 1.94491 : 0.0 : 0.90794
-    // 75 | 1158106 |   17  |    475 | 4.0 : 2.81089 : 0.00000 : 1.44516 : 0.00000 : 1.38000 : 0.12947 : 2.62338 : 0.0 : 0.97880
-    //202 | 1037750 |    8  |    252 | 4.0 : 0.78193 : 0.00000 : 0.51338 : 0.09517 : 0.00000 : 0.04101 : 1.00000 : 0.0 : 0.22724
-    //251 | 2393169 |   37  |    958 | 4.0 : 0.91413 : 0.00000 : 0.66610 : 0.01078 : 0.22913 : 0.05655 : 0.78533 : 0.0 : 0.27396        9.36M lvl 157 i think thats good enough tbh. it got 60k per level...my record is like 32k/lvl
-    // 38 | 2030640 |   40  |   1051 | 4.0 : 0.83434 : 0.00000 : 0.98846 : 0.04482 : 0.09175 : 0.00000 : 0.89960 : 0.0 : 0.34672        7.71M lvl 166
-    let parameters = {
-        if use_best {
-            match check!(train::BestResult::get_best()) {
-                Some(params) => params,
-                None         => logged_panic!("Couldnt find best.log! Have you trained the ai at all?"),
-            }
-        } else {
-            ai::AiParameters {
-                min_lines_to_clear:             4.0,
-                lines_cleared_importance:       0.83434,
-                points_scored_importance:       0.00000,
-                piece_depth_importance:         0.98846,
-                max_height_importance:          0.04482,
-                avg_height_importance:          0.09175,
-                height_variation_importance:    0.00000,
-                current_holes_importance:       0.89960,
-                max_pillar_height:              0.0,
-                current_pillars_importance:     0.34672,
-            }
-        }
-    };
+    let default_parameters = ai::AiParameters::default();
+    let best_parameters = check!(train::BestResult::get_best());
+    if use_best && best_parameters.is_none() {
+        logged_panic!("Couldnt find best.log! Have you trained the ai at all?")
+    }
+    let mut parameters = default_parameters;
 
-    let mut board = check!(Board::new_board());
+    let mut options = menu::Options {
+        settings,
+        handling,
+        ai: if use_best {menu::AiSet::Best} else {menu::AiSet::Default},
+        has_best: best_parameters.is_some(),
+    };
+    let mut state = State::Title(menu::Menu::title());
+    let mut session: Option<Session> = None;
+    let mut leaderboard = check!(highscore::Leaderboard::get());
+    let renderer = check!(game::render::Renderer::new(&options.settings));
     let mut ai_radio = None;
 
     let mut screen = engine::drawing::Screen::new(
-        board.screen_dim.0,
-        board.screen_dim.1
+        renderer.screen_dim.0,
+        renderer.screen_dim.1
     );
     let mut fpslock = engine::game::FpsLock::create_lock(TARGET_FPS);
     let event_loop = engine::game::EventLoop::new();
     let mut input = engine::game::WinitInputHelper::new();
     let mut window = engine::game::Window::init(
         GAME_TITLE,
-        board.screen_dim.0,
-        board.screen_dim.1,
+        renderer.screen_dim.0,
+        renderer.screen_dim.1,
         &event_loop
     );
 
     event_loop.run(move |event, _, control_flow| {
         fpslock.start_frame();
         if let engine::game::Event::RedrawRequested(_) = event {
-            screen.wipe();
-            board.draw(&mut screen);
+            match (&state, &session) {
+                (State::Playing, Some(game)) => {
+                    renderer.draw(&mut screen, &game.board, leaderboard.best(game.board.settings().mode));
+                    renderer.draw_faults(&mut screen, game.finesse.faults);
+                },
+                (State::Paused(menu), Some(game)) | (State::Settings(menu, true), Some(game)) => {
+                    renderer.draw_paused(&mut screen, &game.board, leaderboard.best(game.board.settings().mode));
+                    menu.draw(&mut screen, &options, renderer.screen_dim.0);
+                },
+                (State::Title(menu), _) | (State::Settings(menu, _), _) => {
+                    screen.wipe();
+                    menu.draw(&mut screen, &options, renderer.screen_dim.0);
+                },
+                (State::Scores(scores), _) => {
+                    screen.wipe();
+                    scores.draw(&mut screen, &leaderboard, renderer.screen_dim.0);
+                },
+                _ => screen.wipe(),
+            }
             if ai_radio.is_some() {
                 screen.draw_text((0,0), fpslock.get_fps(), 16.0, &[0xFF;4], engine::drawing::DEBUG_FONT);
             }
@@ -81,67 +203,201 @@ pub fn run(train: bool, auto_loop: bool, use_best: bool) {
         }
 
         if input.update(&event) {
-            if input.key_pressed(engine::game::VirtualKeyCode::P) {
-                ai_radio = {
-                    match ai_radio {
-                        Some(_) => None,
-                        None => Some(ai::start(parameters.clone(), false)),     //bool to turn on debug logging
-                    }
-                }
-            }
-            if ai_radio.is_some() {
-                if let Some(ai_input) = check!(ai_radio.as_ref().unwrap().get_input()) {
-                    match ai_input {
-                        ai::Move::Left      => {board.move_piece(Move::Left);},
-                        ai::Move::Right     => {board.move_piece(Move::Right);},
-                        ai::Move::Rotate    => {board.rotate_piece();}
-                        ai::Move::Drop      => {check!(board.drop_piece());},
-                        ai::Move::Hold      => {check!(board.hold_piece());},
-                        ai::Move::Restart   => if auto_loop {check!(board.reset())},
-                        ai::Move::None      => {},
+            let pressed = |action| controls.keys(action).iter().any(|k| input.key_pressed(*k));
+            let mut next = None;
+            let mut picked = None;
+            match (&mut state, session.as_mut()) {
+                (State::Playing, Some(game)) => {
+                    if pressed(Action::Pause) {next = Some(State::Paused(menu::Menu::pause()))}
+                    if pressed(Action::ToggleAi) {
+                        ai_radio = {
+                            match ai_radio {
+                                Some(_) => None,
+                                None => Some(ai::start(parameters.clone(), search, false)),     //bool to turn on debug logging
+                            }
+                        }
                     }
-                }
-            } else {
-                if input.key_pressed(engine::game::VirtualKeyCode::A)
-                || input.key_pressed(engine::game::VirtualKeyCode::Left)
-                {board.move_piece(Move::Left);}
-
-                if input.key_pressed(engine::game::VirtualKeyCode::S)
-                || input.key_pressed(engine::game::VirtualKeyCode::Down)
-                {board.move_piece(Move::Down);}
-
-
-                if input.key_pressed(engine::game::VirtualKeyCode::D)
-                || input.key_pressed(engine::game::VirtualKeyCode::Right)
-                {board.move_piece(Move::Right);}
+                    if ai_radio.is_some() {
+                        if let Some(ai_input) = check!(ai_radio.as_ref().unwrap().get_input()) {
+                            if ai_input.is_key_press() {game.finesse.pressed()}
+                            match ai_input {
+                                ai::Move::Left      => {check!(game.play(Input::Move(Move::Left)));},
+                                ai::Move::Right     => {check!(game.play(Input::Move(Move::Right)));},
+                                ai::Move::DasLeft   => while check!(game.play(Input::Move(Move::Left))) {},
+                                ai::Move::DasRight  => while check!(game.play(Input::Move(Move::Right))) {},
+                                ai::Move::SoftDrop(cells) => for _ in 0..cells {check!(game.play(Input::Move(Move::Down)));},
+                                ai::Move::Rotate(r) => {check!(game.play(Input::Rotate(r)));},
+                                ai::Move::Drop      => {check!(game.play(Input::Drop));},
+                                ai::Move::Hold      => {check!(game.play(Input::Hold));},
+                                ai::Move::Restart   => if auto_loop {check!(game.restart())},
+                                ai::Move::None      => {},
+                            }
+                        }
+                    } else {
+                        let key = |action| handling::Key {
+                            pressed: pressed(action),
+                            held: controls.keys(action).iter().any(|k| input.key_held(*k)),
+                        };
+                        let moves = [Action::Left, Action::Right, Action::SoftDrop, Action::RotateClockwise, Action::RotateCounterClockwise, Action::Rotate180];
+                        for _ in moves.iter().filter(|action| pressed(**action)) {game.finesse.pressed()}
+                        for (direction, cells) in game.auto_repeat.update(key(Action::Left), key(Action::Right), key(Action::SoftDrop), game.board.speed()) {
+                            for _ in 0..cells {
+                                if !check!(game.play(Input::Move(direction))) {break}
+                            }
+                        }
 
-                if input.key_pressed(engine::game::VirtualKeyCode::W)
-                || input.key_pressed(engine::game::VirtualKeyCode::R)
-                || input.key_pressed(engine::game::VirtualKeyCode::X)
-                || input.key_pressed(engine::game::VirtualKeyCode::Up)
-                {board.rotate_piece();}
+                        if pressed(Action::RotateClockwise)         {check!(game.play(Input::Rotate(Rotate::Clockwise)));}
+                        if pressed(Action::RotateCounterClockwise)  {check!(game.play(Input::Rotate(Rotate::CounterClockwise)));}
+                        if pressed(Action::Rotate180)               {check!(game.play(Input::Rotate(Rotate::Half)));}
+                        if pressed(Action::Hold)                    {check!(game.play(Input::Hold));}
+                        if pressed(Action::HardDrop)                {check!(game.play(Input::Drop));}
 
+                        if pressed(Action::Undo) {
+                            if let Some(snapshot) = check!(game.rewind.undo(&mut game.board)) {
+                                game.replay.rewind(snapshot.frames());
+                                game.replay_saved = false;
+                                game.undone = true;
+                                game.track_finesse();
+                            }
+                        }
+                    }
+                    if pressed(Action::Restart) || pressed(Action::HardDrop) && game.board.gameover {
+                        check!(game.restart());
+                    }
 
-                if input.key_pressed(engine::game::VirtualKeyCode::F)
-                ||input.key_pressed(engine::game::VirtualKeyCode::C)
-                {check!(board.hold_piece());}
+                    //handles updating. nothing runs on the frame the game gets paused
+                    if next.is_none() {
+                        if ai_radio.is_some() {
+                            check!(ai_radio.as_ref().unwrap().send_board(game.board.get_board()));
+                            check!(game.play(Input::Tick));
+                        }
+                        else {check!(game.play(Input::Update));}
+                        game.rewind.track(&game.board);
+                        for event in game.events.try_iter() {
+                            if let Event::GameOver {..} = event {
+                                check!(game.replay.save(REPLAY_FILE));
+                                game.replay_saved = true;
+                                //games with undos dont go on the leaderboard
+                                if !game.undone {
+                                    let name = match (&ai_radio, options.ai) {
+                                        (None, _)                   => name.as_str(),
+                                        (Some(_), menu::AiSet::Best)    => AI_BEST_NAME,
+                                        (Some(_), menu::AiSet::Default) => AI_NAME,
+                                    };
+                                    check!(leaderboard.submit(&game.board, name));
+                                }
+                            }
+                        }
+                    }
+                },
+                (State::Title(menu), _) | (State::Paused(menu), _) | (State::Settings(menu, _), _) => {
+                    picked = menu::Nav::read(&input).and_then(|nav| menu.navigate(nav, &mut options));
+                },
+                (State::Scores(scores), _) => match menu::Nav::read(&input) {
+                    Some(nav) if scores.navigate(nav) => next = Some(State::Title(menu::Menu::title())),
+                    _ => {},
+                },
+                _ => {},
+            }
 
-                if input.key_pressed(engine::game::VirtualKeyCode::Space)
-                {check!(board.drop_piece());}
+            let mut quit = input.quit();
+            match picked {
+                Some(menu::Entry::Start) => {
+                    session = Some(check!(Session::new(options.settings.clone(), options.handling)));
+                    parameters = match (options.ai, &best_parameters) {
+                        (menu::AiSet::Best, Some(best)) => *best,
+                        _ => default_parameters,
+                    };
+                    next = Some(State::Playing);
+                },
+                Some(menu::Entry::Resume) => {
+                    if let Some(game) = session.as_mut() {game.auto_repeat.reset()}
+                    next = Some(State::Playing);
+                },
+                Some(menu::Entry::Restart) => {
+                    if let Some(game) = session.as_mut() {check!(game.restart())}
+                    next = Some(State::Playing);
+                },
+                Some(menu::Entry::Scores) => next = Some(State::Scores(menu::Scores::new(options.settings.mode))),
+                Some(menu::Entry::Settings) => next = Some(State::Settings(menu::Menu::settings(), matches!(state, State::Paused(_)))),
+                Some(menu::Entry::Back) => {
+                    if let Some(game) = session.as_mut() {game.auto_repeat = handling::AutoRepeat::new(options.handling)}
+                    next = Some(match state {
+                        State::Settings(_, true) => State::Paused(menu::Menu::pause()),
+                        _ => State::Title(menu::Menu::title()),
+                    });
+                },
+                Some(menu::Entry::Title) => {
+                    if let Some(mut game) = session.take() {check!(game.save_replay())}
+                    if let Some(mut radio) = ai_radio.take() {check!(radio.join())}
+                    next = Some(State::Title(menu::Menu::title()));
+                },
+                Some(menu::Entry::Quit) => quit = true,
+                _ => {},
             }
-            if input.key_pressed(engine::game::VirtualKeyCode::Return)
-            || input.key_pressed(engine::game::VirtualKeyCode::NumpadEnter)
-            || input.key_pressed(engine::game::VirtualKeyCode::Space) && board.gameover
-                {check!(board.reset())}
+            if let Some(next) = next {state = next}
 
-            if input.key_pressed(engine::game::VirtualKeyCode::Escape) || input.quit() {
+            if quit {
                 *control_flow = engine::game::ControlFlow::Exit;
+                if let Some(game) = session.as_mut() {check!(game.save_replay())}
                 if ai_radio.is_some() {
                     check!(ai_radio.as_mut().unwrap().join());
                 }
                 return;
             }
 
+            if let Some(factor) = input.scale_factor_changed() {
+                window.hidpi_factor = factor;
+            }
+            if let Some(size) = input.window_resized() {
+                window.pixels.resize(size.width, size.height);
+            }
+            window.window.request_redraw();
+        }
+    });
+}
+
+///plays a saved replay back in the window. restart starts it over and pause leaves
+#[cfg(feature = "render")]
+fn play_replay(path: &str, controls: Controls) {
+    let mut playback = check!(Playback::new(check!(Replay::load(path))));
+    let leaderboard = check!(highscore::Leaderboard::get());
+    let renderer = check!(game::render::Renderer::new(playback.board.settings()));
+
+    let mut screen = engine::drawing::Screen::new(
+        renderer.screen_dim.0,
+        renderer.screen_dim.1
+    );
+    let mut fpslock = engine::game::FpsLock::create_lock(TARGET_FPS);
+    let event_loop = engine::game::EventLoop::new();
+    let mut input = engine::game::WinitInputHelper::new();
+    let mut window = engine::game::Window::init(
+        GAME_TITLE,
+        renderer.screen_dim.0,
+        renderer.screen_dim.1,
+        &event_loop
+    );
+
+    event_loop.run(move |event, _, control_flow| {
+        fpslock.start_frame();
+        if let engine::game::Event::RedrawRequested(_) = event {
+            screen.wipe();
+            renderer.draw(&mut screen, &playback.board, leaderboard.best(playback.board.settings().mode));
+            screen.draw_text((0,0), "REPLAY", 16.0, &[0xFF;4], engine::drawing::DEBUG_FONT);
+            screen.flatten(window.pixels.get_frame());
+            window.pixels.render().unwrap();
+            fpslock.end_frame();
+        }
+
+        if input.update(&event) {
+            let pressed = |action| controls.keys(action).iter().any(|k| input.key_pressed(*k));
+            if pressed(Action::Restart) {check!(playback.restart())}
+
+            if pressed(Action::Pause) || input.quit() {
+                *control_flow = engine::game::ControlFlow::Exit;
+                return;
+            }
+
             if let Some(factor) = input.scale_factor_changed() {
                 window.hidpi_factor = factor;
             }
@@ -149,10 +405,8 @@ pub fn run(train: bool, auto_loop: bool, use_best: bool) {
                 window.pixels.resize(size.width, size.height);
             }
 
-            //handles updating
-            if ai_radio.is_some() {check!(ai_radio.as_ref().unwrap().send_board(board.get_board()))}
-            else {check!(board.try_update());}
+            check!(playback.step());
             window.window.request_redraw();
         }
     });
-}
\ No newline at end of file
+}
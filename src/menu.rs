@@ -0,0 +1,273 @@
+//! the title, pause and settings menus. drawn with the engines debug font.
+//! menus use their own fixed keys instead of the controls file so a bad binding cant lock you out of them
+
+use crate::game::{Mode, RandomizerType, Settings};
+use crate::game::mode::format_frames;
+use crate::handling::{Handling, INSTANT};
+use crate::highscore::{Leaderboard, format_date};
+
+use engine::drawing;
+use engine::game::{VirtualKeyCode, WinitInputHelper};
+
+///the color of menu text
+const TEXT_COLOR: [u8;4]        = [0xFF;4];
+///the color of the selected entry
+const SELECTED_COLOR: [u8;4]    = [0xFF, 0xFF, 0x00, 0xFF];
+///the size of menu titles
+const TITLE_SIZE: f32           = 64.0;
+///the size of menu entries
+const ENTRY_SIZE: f32           = 24.0;
+///the height of the menu title in pixels
+const TITLE_Y: isize            = 40;
+///the height of the first entry in pixels
+const ENTRY_Y: isize            = 160;
+///the vertical space each entry gets in pixels
+const ENTRY_SPACING: isize      = 40;
+///roughly how wide menus are in pixels. used to center them
+const MENU_WIDTH: isize         = 288;
+///the size of leaderboard rows
+const SCORE_SIZE: f32           = 16.0;
+///the vertical space each leaderboard row gets in pixels
+const SCORE_SPACING: isize      = 24;
+///how many characters of a name the leaderboard shows
+const NAME_WIDTH: usize         = 10;
+///the longest das the settings menu goes up to
+const MAX_DAS: usize            = 30;
+///the slowest arr the settings menu goes up to
+const MAX_ARR: usize            = 10;
+///the biggest soft drop factor the settings menu goes up to
+const MAX_SDF: usize            = 40;
+
+const UP_KEYS: &[VirtualKeyCode]        = &[VirtualKeyCode::Up, VirtualKeyCode::W];
+const DOWN_KEYS: &[VirtualKeyCode]      = &[VirtualKeyCode::Down, VirtualKeyCode::S];
+const LEFT_KEYS: &[VirtualKeyCode]      = &[VirtualKeyCode::Left, VirtualKeyCode::A];
+const RIGHT_KEYS: &[VirtualKeyCode]     = &[VirtualKeyCode::Right, VirtualKeyCode::D];
+const SELECT_KEYS: &[VirtualKeyCode]    = &[VirtualKeyCode::Return, VirtualKeyCode::NumpadEnter, VirtualKeyCode::Space];
+const BACK_KEYS: &[VirtualKeyCode]      = &[VirtualKeyCode::Escape, VirtualKeyCode::Back];
+
+///a key press in a menu
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+}
+
+impl Nav {
+    ///gets the menu key pressed this frame if there is one
+    pub fn read(input: &WinitInputHelper) -> Option<Self> {
+        let pressed = |keys: &[VirtualKeyCode]| keys.iter().any(|k| input.key_pressed(*k));
+        match () {
+            _ if pressed(UP_KEYS)       => Some(Self::Up),
+            _ if pressed(DOWN_KEYS)     => Some(Self::Down),
+            _ if pressed(LEFT_KEYS)     => Some(Self::Left),
+            _ if pressed(RIGHT_KEYS)    => Some(Self::Right),
+            _ if pressed(SELECT_KEYS)   => Some(Self::Select),
+            _ if pressed(BACK_KEYS)     => Some(Self::Back),
+            _ => None,
+        }
+    }
+}
+
+///which parameters the ai plays with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AiSet {
+    ///the parameters built into the game
+    Default,
+    ///the top result in best.log
+    Best,
+}
+
+///everything the menus can change
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub settings: Settings,
+    pub handling: Handling,
+    pub ai: AiSet,
+    ///false if theres no best.log to pick AiSet::Best from
+    pub has_best: bool,
+}
+
+impl Options {
+    ///steps a setting forward or back through its values. does nothing if entry isnt a setting
+    fn change(&mut self, entry: Entry, step: isize) {
+        match entry {
+            Entry::Mode         => self.settings.mode = cycle(&Mode::ALL, self.settings.mode, step),
+            Entry::Randomizer   => self.settings.randomizer = cycle(&RandomizerType::ALL, self.settings.randomizer, step),
+            Entry::Ai if self.has_best => self.ai = cycle(&[AiSet::Default, AiSet::Best], self.ai, step),
+            Entry::Das          => self.handling.das = bump(self.handling.das, step, MAX_DAS),
+            Entry::Arr          => self.handling.arr = bump(self.handling.arr, step, MAX_ARR),
+            Entry::SoftDrop     => self.handling.soft_drop_factor = bump(self.handling.soft_drop_factor, step, MAX_SDF),
+            _ => {},
+        }
+    }
+}
+
+///gets the value step places away from current in all. wraps around
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, step: isize) -> T {
+    let i = all.iter().position(|v| *v == current).unwrap_or(0) as isize;
+    all[(i+step).rem_euclid(all.len() as isize) as usize]
+}
+
+///adds step to value without going below 0 or above max
+fn bump(value: usize, step: isize, max: usize) -> usize {
+    ((value as isize+step).max(0) as usize).min(max)
+}
+
+///shows a handling timing. 0 is instant
+fn timing(value: usize) -> String {
+    match value {
+        INSTANT => String::from("INSTANT"),
+        value   => value.to_string(),
+    }
+}
+
+///a line in a menu
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Entry {
+    Start,
+    Resume,
+    Restart,
+    Settings,
+    Scores,
+    Title,
+    Quit,
+    Back,
+    Mode,
+    Randomizer,
+    Ai,
+    Das,
+    Arr,
+    SoftDrop,
+}
+
+impl Entry {
+    ///the text shown for the entry
+    fn label(&self, options: &Options) -> String {
+        match self {
+            Self::Start         => String::from("START"),
+            Self::Resume        => String::from("RESUME"),
+            Self::Restart       => String::from("RESTART"),
+            Self::Settings      => String::from("SETTINGS"),
+            Self::Scores        => String::from("SCORES"),
+            Self::Title         => String::from("QUIT TO TITLE"),
+            Self::Quit          => String::from("QUIT"),
+            Self::Back          => String::from("BACK"),
+            Self::Mode          => format!("MODE: {}", options.settings.mode.to_string().to_uppercase()),
+            Self::Randomizer    => format!("PIECES: {}", options.settings.randomizer.to_string().to_uppercase()),
+            Self::Ai            => format!("AI: {}", if options.ai == AiSet::Best {"BEST.LOG"} else {"DEFAULT"}),
+            Self::Das           => format!("DAS: {}", timing(options.handling.das)),
+            Self::Arr           => format!("ARR: {}", timing(options.handling.arr)),
+            Self::SoftDrop      => format!("SDF: {}", timing(options.handling.soft_drop_factor)),
+        }
+    }
+}
+
+///a list of entries with one selected
+#[derive(Clone, Debug)]
+pub struct Menu {
+    title: &'static str,
+    entries: &'static [Entry],
+    ///what pressing back picks
+    back: Entry,
+    selected: usize,
+}
+
+impl Menu {
+    ///the menu the game opens on
+    pub fn title() -> Self {
+        Self::new("TETRIS", &[Entry::Start, Entry::Mode, Entry::Randomizer, Entry::Ai, Entry::Settings, Entry::Scores, Entry::Quit], Entry::Quit)
+    }
+
+    ///the menu shown while the game is paused
+    pub fn pause() -> Self {
+        Self::new("PAUSED", &[Entry::Resume, Entry::Restart, Entry::Settings, Entry::Title, Entry::Quit], Entry::Resume)
+    }
+
+    ///the handling settings. can be opened from the title or mid game
+    pub fn settings() -> Self {
+        Self::new("SETTINGS", &[Entry::Das, Entry::Arr, Entry::SoftDrop, Entry::Back], Entry::Back)
+    }
+
+    fn new(title: &'static str, entries: &'static [Entry], back: Entry) -> Self {
+        Self {title, entries, back, selected: 0}
+    }
+
+    ///moves the selection or changes the selected setting. returns the entry that was picked
+    pub fn navigate(&mut self, nav: Nav, options: &mut Options) -> Option<Entry> {
+        let entry = self.entries[self.selected];
+        match nav {
+            Nav::Up     => self.selected = (self.selected+self.entries.len()-1)%self.entries.len(),
+            Nav::Down   => self.selected = (self.selected+1)%self.entries.len(),
+            Nav::Left   => options.change(entry, -1),
+            Nav::Right  => options.change(entry, 1),
+            Nav::Select => {
+                options.change(entry, 1);
+                return Some(entry)
+            },
+            Nav::Back   => return Some(self.back),
+        }
+        None
+    }
+
+    ///draws the menu centered on a screen width pixels wide
+    pub fn draw(&self, screen: &mut drawing::Screen, options: &Options, width: usize) {
+        let x = width as isize/2 - MENU_WIDTH/2;
+        screen.draw_text((x, TITLE_Y), self.title, TITLE_SIZE, &TEXT_COLOR, drawing::DEBUG_FONT);
+        for (i, entry) in self.entries.iter().enumerate() {
+            let (text, color) = match i == self.selected {
+                true    => (format!("> {}", entry.label(options)), &SELECTED_COLOR),
+                false   => (format!("  {}", entry.label(options)), &TEXT_COLOR),
+            };
+            screen.draw_text((x, ENTRY_Y + i as isize*ENTRY_SPACING), &text, ENTRY_SIZE, color, drawing::DEBUG_FONT);
+        }
+    }
+}
+
+
+
+///the leaderboard screen. shows one mode at a time
+#[derive(Clone, Debug)]
+pub struct Scores {
+    mode: Mode,
+}
+
+impl Scores {
+    ///opens on mode
+    pub fn new(mode: Mode) -> Self {
+        Self {mode}
+    }
+
+    ///left and right flip through the modes. returns true once its closed
+    pub fn navigate(&mut self, nav: Nav) -> bool {
+        match nav {
+            Nav::Left | Nav::Up     => self.mode = cycle(&Mode::ALL, self.mode, -1),
+            Nav::Right | Nav::Down  => self.mode = cycle(&Mode::ALL, self.mode, 1),
+            Nav::Select | Nav::Back => return true,
+        }
+        false
+    }
+
+    ///draws the modes table centered on a screen width pixels wide
+    pub fn draw(&self, screen: &mut drawing::Screen, leaderboard: &Leaderboard, width: usize) {
+        let x = width as isize/2 - MENU_WIDTH/2;
+        screen.draw_text((x, TITLE_Y), "SCORES", TITLE_SIZE, &TEXT_COLOR, drawing::DEBUG_FONT);
+        let title = format!("< {} >", self.mode.to_string().to_uppercase());
+        screen.draw_text((x, ENTRY_Y-ENTRY_SPACING), &title, ENTRY_SIZE, &SELECTED_COLOR, drawing::DEBUG_FONT);
+        let entries = leaderboard.entries(self.mode);
+        if entries.is_empty() {
+            screen.draw_text((x, ENTRY_Y), "NO GAMES YET", SCORE_SIZE, &TEXT_COLOR, drawing::DEBUG_FONT);
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            let name = entry.name.chars().take(NAME_WIDTH).collect::<String>().to_uppercase();
+            let row = format!(
+                "{:>2} {:<name$} {:>8} L{:<2} {:>3}L {} {}",
+                i+1, name, entry.score, entry.level, entry.lines, format_frames(entry.frames), format_date(entry.date), name=NAME_WIDTH
+            );
+            screen.draw_text((x/4, ENTRY_Y + i as isize*SCORE_SPACING), &row, SCORE_SIZE, &TEXT_COLOR, drawing::DEBUG_FONT);
+        }
+    }
+}
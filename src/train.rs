@@ -3,7 +3,7 @@ mod breed;
 mod progress;
 mod display;
 
-use super::game::{Board, Move};
+use super::game::{Board, Move, Settings, RandomizerType, Mode, GarbageHoles, Gravity};
 use super::ai;
 pub use progress::BestResult;
 use dynerr::*;
@@ -13,6 +13,7 @@ use std::time::Instant;
 use std::sync::{Arc, Mutex, mpsc};
 
 use threadpool::ThreadPool;
+use rand::Rng;
 
 ///times to run each AIs game
 const SIM_TIMES: usize          = 50;   //50
@@ -24,6 +25,22 @@ const BATCH_SIZE: usize         = 200;  //200
 const GENERATIONS: usize        = 0;    //IF 0 THEN INFINITE
 ///max level before timeout
 const MAX_LEVEL: usize          = 50;   //20
+///the randomizer the sims are played with
+const RANDOMIZER: RandomizerType = RandomizerType::Nes;
+///the mode the sims are played in. sprint times get shown next to scores
+const MODE: Mode = Mode::Endless;
+///how many pieces between each garbage line pushed onto the sims. 0 for no garbage
+const GARBAGE_RATE: usize = 0;
+///the width and height of the board the sims are played on
+const BOARD_SIZE: (usize, usize) = (10, 20);
+///if the sims pieces fall on their own. off means the AI only has to worry about its own moves
+const USE_GRAVITY: bool = false;
+///how fast the sims pieces fall when USE_GRAVITY is on. Gravity::Guideline hits 20G at level 18
+const GRAVITY: Gravity = Gravity::Nes;
+///how many lines the sims clear per level
+const LINES_PER_LEVEL: usize = 10;
+///how far ahead the sims AI looks. anything past greedy makes training a lot slower
+const SEARCH: ai::Search = ai::Search::GREEDY;
 
 //range that usize parameters can be between
 const U_RANGE: (usize, usize)   = (0, 4);       //max *should* be 4. inclusive upper
@@ -53,6 +70,7 @@ pub struct GameResult {
     score: usize,
     level: usize,
     placed: usize,
+    frames: usize,                              //not saved to best.log
     parameters: Option<ai::AiParameters>,       //an option to cut down on clones.
 }
 
@@ -63,6 +81,11 @@ impl GameResult {
             score:  results.iter().map(|r| r.score).sum::<usize>()/results.len(),
             level:  results.iter().map(|r| r.level).sum::<usize>()/results.len(),
             placed: results.iter().map(|r|r.placed).sum::<usize>()/results.len(),
+            frames: {
+                //only games that finished the mode have a time
+                let finished = results.iter().filter(|r| r.frames > 0).map(|r| r.frames).collect::<Vec<usize>>();
+                finished.iter().sum::<usize>().checked_div(finished.len()).unwrap_or(0)
+            },
             parameters: Some(parameters),                                  
         }
     }
@@ -93,22 +116,41 @@ impl fmt::Display for GameResult {
 
 
 
-///plays a game SIM_TIMES times
-fn play_game(board: Arc<Board>, parameters: ai::AiParameters, progress: Arc<Mutex<usize>>) -> GameResult {
+///plays a game for each seed. every AI in a generation gets the same seeds so they see the same pieces
+fn play_game(seeds: Arc<Vec<u64>>, parameters: ai::AiParameters, progress: Arc<Mutex<usize>>) -> GameResult {
     let mut results = Vec::new();
-    let mut ai_radio = ai::start(parameters, false);
-    for _ in 0..SIM_TIMES {
-        let mut sim_board = (*board).clone();
-        let mut placed = 0;
+    let mut ai_radio = ai::start(parameters, SEARCH, false);
+    for seed in seeds.iter() {
+        let settings = Settings {
+            mode: MODE,
+            randomizer: RANDOMIZER,
+            width: BOARD_SIZE.0,
+            height: BOARD_SIZE.1,
+            gravity: GRAVITY,
+            lines_per_level: LINES_PER_LEVEL,
+            ..Settings::default()
+        };
+        let mut sim_board = check!(Board::with_settings(settings, *seed));
+        let mut placed: usize = 0;
         while !sim_board.gameover && sim_board.level < MAX_LEVEL {
             check!(ai_radio.send_board(sim_board.get_board()));
+            //one input per frame like the real game
+            if USE_GRAVITY {check!(sim_board.try_update());}
+            else {sim_board.tick();}
             loop {
                 if let Some(ai_input) = check!(ai_radio.get_input()) {
                     match ai_input {
                         ai::Move::Left      => {sim_board.move_piece(Move::Left);},
                         ai::Move::Right     => {sim_board.move_piece(Move::Right);},
-                        ai::Move::Rotate    => {sim_board.rotate_piece();}
-                        ai::Move::Drop      => {check!(sim_board.drop_piece());placed+=1;},
+                        ai::Move::DasLeft   => while sim_board.move_piece(Move::Left) {},
+                        ai::Move::DasRight  => while sim_board.move_piece(Move::Right) {},
+                        ai::Move::SoftDrop(cells) => for _ in 0..cells {sim_board.move_piece(Move::Down);},
+                        ai::Move::Rotate(r) => {sim_board.rotate_piece(r);},
+                        ai::Move::Drop      => {
+                            check!(sim_board.drop_piece());
+                            placed+=1;
+                            if GARBAGE_RATE != 0 && placed.is_multiple_of(GARBAGE_RATE) {sim_board.add_garbage(1, GarbageHoles::Random);}
+                        },
                         ai::Move::Hold      => {check!(sim_board.hold_piece());},
                         ai::Move::Restart   => {},
                         ai::Move::None      => {},
@@ -123,6 +165,7 @@ fn play_game(board: Arc<Board>, parameters: ai::AiParameters, progress: Arc<Mute
                 score: sim_board.score,
                 level: sim_board.level,
                 placed,
+                frames: if sim_board.completed {sim_board.frames()} else {0},
             }
         );
         *(progress.lock().unwrap())+=1;
@@ -135,17 +178,18 @@ fn play_game(board: Arc<Board>, parameters: ai::AiParameters, progress: Arc<Mute
 
 ///takes Vec<Parameters> and does generation
 fn do_generation(generation: Vec<ai::AiParameters>) -> DynResult<Vec<GameResult>> {
-    let master_board = Arc::new(Board::new_board()?);
+    let mut rng = rand::thread_rng();
+    let seeds = Arc::new((0..SIM_TIMES).map(|_| rng.gen()).collect::<Vec<u64>>());
     let progress = Arc::new(Mutex::new(0));
     let display_thread = display::DisplayThread::start(Arc::clone(&progress));
     let (tx, rx) = mpsc::channel();
 
     let pool = ThreadPool::new(POOL_SIZE);
     for child in generation {
-        let board_ref = Arc::clone(&master_board);
+        let seeds = Arc::clone(&seeds);
         let progress = Arc::clone(&progress);
         let tx = tx.clone();
-        pool.execute(move || check!(tx.send(play_game(board_ref, child, progress))));
+        pool.execute(move || check!(tx.send(play_game(seeds, child, progress))));
     }
     pool.join();
 
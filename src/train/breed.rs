@@ -19,6 +19,13 @@ fn random_param() -> ai::AiParameters {
         current_holes_importance:       rng.gen_range(F_RANGE.0, F_RANGE.1),
         max_pillar_height:              rng.gen_range(U_RANGE.0, U_RANGE.1+1) as f32,
         current_pillars_importance:     rng.gen_range(F_RANGE.0, F_RANGE.1),
+        row_transitions_importance:     rng.gen_range(F_RANGE.0, F_RANGE.1),
+        column_transitions_importance:  rng.gen_range(F_RANGE.0, F_RANGE.1),
+        well_depth_importance:          rng.gen_range(F_RANGE.0, F_RANGE.1),
+        hole_depth_importance:          rng.gen_range(F_RANGE.0, F_RANGE.1),
+        rows_with_holes_importance:     rng.gen_range(F_RANGE.0, F_RANGE.1),
+        eroded_cells_importance:        rng.gen_range(F_RANGE.0, F_RANGE.1),
+        landing_height_importance:      rng.gen_range(F_RANGE.0, F_RANGE.1),
     }
 }
 
@@ -38,7 +45,7 @@ pub fn sort(results: &mut Vec<GameResult>) {
 
 
 //gets two different random sets of ai parameters from vec
-fn get_couple(params: &Vec<[f32;10]>, mut rng: ThreadRng) -> ([f32;10], [f32;10]){
+fn get_couple(params: &Vec<[f32; ai::PARAMETER_COUNT]>, mut rng: ThreadRng) -> ([f32; ai::PARAMETER_COUNT], [f32; ai::PARAMETER_COUNT]){
     let couple = {
         let (mut x, mut y) = (rng.gen_range(0,params.len()), rng.gen_range(0,params.len()));
         while x==y {
@@ -53,7 +60,7 @@ fn get_couple(params: &Vec<[f32;10]>, mut rng: ThreadRng) -> ([f32;10], [f32;10]
 
 
 //performs evolutionary gene crossover
-fn crossover_genes(params: &Vec<[f32;10]>, mut rng: ThreadRng) -> Vec<[f32;10]> {
+fn crossover_genes(params: &Vec<[f32; ai::PARAMETER_COUNT]>, mut rng: ThreadRng) -> Vec<[f32; ai::PARAMETER_COUNT]> {
     let mut kids = Vec::new();
     for _ in 0..(BATCH_SIZE as f32*PERCENT_CROSS) as usize/2 {
         let (male, fema) = get_couple(params, rng);
@@ -70,11 +77,11 @@ fn crossover_genes(params: &Vec<[f32;10]>, mut rng: ThreadRng) -> Vec<[f32;10]>
 
 
 //swaps random genes
-fn insert_genes(params: &Vec<[f32;10]>, mut rng: ThreadRng) -> Vec<[f32;10]> {
+fn insert_genes(params: &Vec<[f32; ai::PARAMETER_COUNT]>, mut rng: ThreadRng) -> Vec<[f32; ai::PARAMETER_COUNT]> {
     let mut kids = Vec::new();
     for _ in 0..(BATCH_SIZE as f32*PERCENT_INSERT) as usize {
         let (male, fema) = get_couple(params, rng);
-        let mut kid = [0.0;10];
+        let mut kid = [0.0; ai::PARAMETER_COUNT];
         for x in 0..kid.len() {
             if rng.gen_range(0.0, 1.0) <= INSERT_CHANCE {kid[x] = fema[x]}
             else {kid[x] = male[x]}
@@ -87,7 +94,7 @@ fn insert_genes(params: &Vec<[f32;10]>, mut rng: ThreadRng) -> Vec<[f32;10]> {
 
 
 //mutates random genes by adding and subtracting small amounts
-fn nudge_genes(kids: &mut Vec<[f32;10]>, mut rng: ThreadRng) {
+fn nudge_genes(kids: &mut Vec<[f32; ai::PARAMETER_COUNT]>, mut rng: ThreadRng) {
     for nudge in kids {
         for (i, gene) in nudge.iter_mut().enumerate() {
             if rng.gen_range(0.0, 1.0) <= NUDGE_CHANCE {
@@ -114,7 +121,7 @@ fn nudge_genes(kids: &mut Vec<[f32;10]>, mut rng: ThreadRng) {
 
 
 //mutates random genes to random number in range
-fn mutate_genes(kids: &mut Vec<[f32;10]>, mut rng: ThreadRng) {
+fn mutate_genes(kids: &mut Vec<[f32; ai::PARAMETER_COUNT]>, mut rng: ThreadRng) {
     for cronenberg in kids {
         for (i, gene) in cronenberg.iter_mut().enumerate() {
             if rng.gen_range(0.0, 1.0) <= MUTATION_CHANCE {
@@ -135,7 +142,7 @@ fn mutate_genes(kids: &mut Vec<[f32;10]>, mut rng: ThreadRng) {
 pub fn breed_next_gen(breeders: &[GameResult]) -> Vec<ai::AiParameters> {
     let rng = rand::thread_rng();
     let mut kids = Vec::with_capacity(BATCH_SIZE);
-    let params = breeders.iter().map(|b|b.parameters.unwrap().deconstruct()).collect::<Vec<[f32;10]>>();
+    let params = breeders.iter().map(|b|b.parameters.unwrap().deconstruct()).collect::<Vec<[f32; ai::PARAMETER_COUNT]>>();
     //crossover
     kids.extend(crossover_genes(&params, rng));
     //insert
@@ -1,4 +1,5 @@
 use super::*;
+use crate::game::mode::format_frames;
 
 use std::thread;
 use std::time::Duration;
@@ -84,6 +85,13 @@ pub fn display_gen_info(time_handle: &TimeTracker, gen: usize, results: &Vec<Gam
         results[0..disp_num].iter().map(|r| r.score).sum::<usize>()/disp_num,
         results[0..disp_num].iter().map(|r| r.level).sum::<usize>()/disp_num,
     );
+    if MODE.is_timed() {
+        println!("{} Ao{}   {}",
+            MODE.to_string().to_uppercase(),
+            disp_num,
+            format_frames(results[0..disp_num].iter().map(|r| r.frames).sum::<usize>()/disp_num),
+        );
+    }
     GameResult::print_header();
     let disp_num = {if BATCH_SIZE >= 5 {5} else {BATCH_SIZE}};
     for i in 0..disp_num {
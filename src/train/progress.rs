@@ -15,17 +15,22 @@ macro_rules! params_parse {
 }
 
 
-///takes iterator of strings representing GameResults and attempts to parse it
+///takes iterator of strings representing GameResults and attempts to parse it.
+///logs from before the newer parameters were added get 0 for them so they play the same as they used to
 fn parse_game_result(mut fields: Split<char> ) -> DynResult<GameResult> {
+    let score = params_parse!(fields);
+    let level = params_parse!(fields);
+    let placed = params_parse!(fields);
+    let mut parameters = fields.next().ok_or("Failed to parse params")?.split(':').map(|p|
+        Ok(p.replace(" ","").parse()?)
+    ).collect::<DynResult<Vec<f32>>>()?;
+    if parameters.len() < ai::PARAMETER_COUNT {parameters.resize(ai::PARAMETER_COUNT, 0.0)}
     Ok(GameResult {
-        score: params_parse!(fields),
-        level: params_parse!(fields),
-        placed: params_parse!(fields),
-        parameters: Some(ai::AiParameters::construct(
-            fields.next().ok_or("Failed to parse params")?.split(':').map(|p|
-                Ok(p.replace(" ","").parse()?)
-            ).collect::<DynResult<Vec<f32>>>()?.as_slice().try_into()?
-        ))
+        score,
+        level,
+        placed,
+        frames: 0,
+        parameters: Some(ai::AiParameters::construct(parameters.as_slice().try_into()?))
     })
 }
 